@@ -5,9 +5,11 @@ mod tests {
     use ark_std::{end_timer, start_timer};
     use bus_mapping::circuit_input_builder::{BuilderClient, CircuitsParams};
     use env_logger::Env;
+    use eth_types::{geth_types::Transaction as GethTransaction, Bytes};
     use halo2_proofs::{
+        dev::MockProver,
         halo2curves::bn256::{Bn256, Fr, G1Affine},
-        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem},
         poly::{
             commitment::ParamsProver,
             kzg::{
@@ -21,16 +23,23 @@ mod tests {
         },
     };
     use log;
+    use mock::{AddrOrWallet, MockTransaction, MOCK_ACCOUNTS};
     use rand::SeedableRng;
     use rand_chacha::ChaCha20Rng;
-    use std::env::var;
+    use std::{env::var, time::Instant};
     use zkevm_circuits::{
-        tx_circuit::TestTxCircuit as TxCircuit, util::SubCircuit, witness::block_convert,
+        tx_circuit::{TestTxCircuit as TxCircuit, TxCircuit as InnerTxCircuit},
+        util::{log2_ceil, SubCircuit},
+        witness::{block_convert, Transaction},
     };
 
     use bus_mapping::rpc::GethClient;
     use ethers::providers::Http;
     use url::Url;
+
+    // Approximate value, adjust with changes on the TxCircuit.
+    const ROWS_PER_TX: usize = 175_000;
+
     fn get_client() -> GethClient<Http> {
         let geth_url = "http://52.37.45.56:30303";
         let transport = Http::new(Url::parse(geth_url).expect("invalid url"));
@@ -72,9 +81,6 @@ mod tests {
     }
 
     fn build_circuit_from_mock_txs() -> (usize, TxCircuit<Fr>) {
-        // Approximate value, adjust with changes on the TxCircuit.
-        const ROWS_PER_TX: usize = 175_000;
-
         const MAX_CALLDATA: usize = 1024;
         let degree: u32 = var("DEGREE")
             .unwrap_or_else(|_| "19".to_string())
@@ -164,4 +170,162 @@ mod tests {
         .expect("failed to verify bench circuit");
         end_timer!(start3);
     }
+
+    /// Mix of tx kinds used to fill the tx circuit in the witness generation benchmark.
+    #[derive(Clone, Copy, Debug)]
+    enum TxMix {
+        /// Transfers without call data
+        Transfer,
+        /// Calls with `CALLDATA_PER_TX` bytes of call data
+        Calldata,
+        /// Contract creations with `CALLDATA_PER_TX` bytes of init code
+        Create,
+        /// Round robin over the above
+        Mixed,
+    }
+
+    const CALLDATA_PER_TX: usize = 256;
+
+    /// Signed eip155 txs, the only type the mock txs can be signed with.
+    fn build_mock_txs(num_txs: usize, mix: TxMix, rng: &mut ChaCha20Rng) -> Vec<MockTransaction> {
+        (0..num_txs)
+            .map(|i| {
+                let mix = match mix {
+                    TxMix::Mixed => [TxMix::Transfer, TxMix::Calldata, TxMix::Create][i % 3],
+                    mix => mix,
+                };
+                let mut tx = MockTransaction::default();
+                tx.transaction_idx(i as u64 + 1)
+                    .from(AddrOrWallet::random(rng))
+                    .nonce((i as u64).into());
+                match mix {
+                    TxMix::Transfer => {
+                        tx.to(MOCK_ACCOUNTS[i % MOCK_ACCOUNTS.len()]);
+                    }
+                    TxMix::Calldata => {
+                        tx.to(MOCK_ACCOUNTS[i % MOCK_ACCOUNTS.len()])
+                            .input(Bytes::from(vec![0xab; CALLDATA_PER_TX]));
+                    }
+                    TxMix::Create => {
+                        tx.input(Bytes::from(vec![0xab; CALLDATA_PER_TX]));
+                    }
+                    TxMix::Mixed => unreachable!(),
+                }
+                tx.build()
+            })
+            .collect()
+    }
+
+    /// Peak resident set size of the process in kB, read from `/proc/self/status`.
+    fn peak_rss_kb() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))
+            .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+    }
+
+    /// Resets the peak resident set size so that each case reports its own peak.
+    fn reset_peak_rss() {
+        // best effort: only supported on linux
+        let _ = std::fs::write("/proc/self/clear_refs", "5");
+    }
+
+    /// Measures the witness generation of the tx circuit, i.e. the sign data and keccak
+    /// inputs of the txs and `assign` (through `MockProver::run`, which doesn't verify the
+    /// constraints) for varying `max_txs`, `max_calldata` and tx mixes.
+    ///
+    /// The circuit is the tx circuit tester, so `assign` also includes the sig circuit
+    /// that the tx circuit looks up. The cases can be restricted with `MAX_TXS` (a
+    /// comma separated list) and `TX_MIX` (one of transfer, calldata, create, mixed).
+    #[cfg_attr(not(feature = "benches"), ignore)]
+    #[test]
+    fn bench_tx_circuit_witness_gen() {
+        let _ = env_logger::Builder::from_env(Env::default().default_filter_or("info")).try_init();
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        // Unique string used by bench results module for parsing the result
+        const BENCHMARK_ID: &str = "Tx Circuit witness generation";
+
+        let max_txs_cases: Vec<usize> = var("MAX_TXS")
+            .unwrap_or_else(|_| "1,4,16".to_string())
+            .split(',')
+            .map(|max_txs| {
+                max_txs
+                    .trim()
+                    .parse()
+                    .expect("Cannot parse MAX_TXS env var")
+            })
+            .collect();
+        let mixes = match var("TX_MIX").as_deref() {
+            Ok("transfer") => vec![TxMix::Transfer],
+            Ok("calldata") => vec![TxMix::Calldata],
+            Ok("create") => vec![TxMix::Create],
+            Ok("mixed") => vec![TxMix::Mixed],
+            Ok(mix) => panic!("unknown TX_MIX {mix}"),
+            Err(_) => vec![
+                TxMix::Transfer,
+                TxMix::Calldata,
+                TxMix::Create,
+                TxMix::Mixed,
+            ],
+        };
+
+        for &max_txs in &max_txs_cases {
+            let max_calldata = max_txs * CALLDATA_PER_TX;
+            for &mix in &mixes {
+                let mock_txs = build_mock_txs(max_txs, mix, &mut rng);
+                let geth_txs: Vec<GethTransaction> = mock_txs
+                    .iter()
+                    .cloned()
+                    .map(GethTransaction::from)
+                    .collect();
+                let txs: Vec<Transaction> = mock_txs.into_iter().map(Transaction::from).collect();
+                let case = format!(
+                    "{BENCHMARK_ID} with max_txs = {max_txs}, max_calldata = {max_calldata}, mix = {mix:?}"
+                );
+                reset_peak_rss();
+
+                let start = Instant::now();
+                let keccak_inputs =
+                    bus_mapping::circuit_input_builder::keccak_inputs_tx_circuit(&geth_txs)
+                        .expect("keccak inputs of the txs");
+                let keccak_time = start.elapsed();
+
+                let start = Instant::now();
+                let tx_circuit = InnerTxCircuit::<Fr>::new(
+                    max_txs,
+                    max_calldata,
+                    mock::MOCK_CHAIN_ID,
+                    0,
+                    txs.clone(),
+                );
+                let sign_datas = tx_circuit.sig_table_inputs().expect("sign data of the txs");
+                let sign_data_time = start.elapsed();
+
+                let circuit =
+                    TxCircuit::<Fr>::new(max_txs, max_calldata, mock::MOCK_CHAIN_ID, 0, txs);
+                let (config, _) = TxCircuit::<Fr>::configure(&mut ConstraintSystem::default());
+                let k = tx_circuit
+                    .recommended_k(config.tx_config())
+                    .max(log2_ceil(max_txs * ROWS_PER_TX));
+                let start = Instant::now();
+                MockProver::<Fr>::run(k, &circuit, circuit.instance())
+                    .expect("tx circuit assignment should not fail");
+                let assign_time = start.elapsed();
+
+                let rows = InnerTxCircuit::<Fr>::min_num_rows(max_txs, max_calldata);
+                log::info!(
+                    "{case}: {} keccak inputs in {keccak_time:?}, {} sign data in {sign_data_time:?}",
+                    keccak_inputs.len(),
+                    sign_datas.len(),
+                );
+                log::info!(
+                    "{case}: assign of {rows} tx circuit rows (k = {k}) in {assign_time:?}, {:.0} rows/sec, peak memory = {}",
+                    rows as f64 / assign_time.as_secs_f64(),
+                    peak_rss_kb().map_or("n/a".to_string(), |kb| format!("{} MiB", kb / 1024)),
+                );
+            }
+        }
+    }
 }
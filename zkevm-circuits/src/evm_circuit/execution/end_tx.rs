@@ -94,6 +94,14 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         // rwc_delta = 4
 
         let effective_refund = MinMaxGadget::construct(cb, max_refund.quotient(), refund.expr());
+        // With strict-refund-cap, the refund accumulated in TxRefund must not exceed the
+        // cap, instead of being capped to it
+        #[cfg(feature = "strict-refund-cap")]
+        cb.require_equal(
+            "refund <= gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED",
+            effective_refund.min(),
+            refund.expr(),
+        );
 
         // Add effective_refund * tx_gas_price back to caller's balance
         let mul_gas_price_by_refund = MulWordByU64Gadget::construct(
@@ -470,9 +478,11 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 mod test {
     use crate::test_util::CircuitTestBuilder;
     use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{self, bytecode};
+    use eth_types::{self, bytecode, evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Bytecode, Word};
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
@@ -525,6 +535,60 @@ mod test {
         );
     }
 
+    #[test]
+    fn end_tx_gadget_refund() {
+        // clearing a slot refunds 4800 gas, which only exceeds gas_used / 5 once more
+        // than one slot is cleared. Such a refund is capped, unless strict-refund-cap
+        // rejects it.
+        for (num_cleared_slots, is_capped) in [(1u64, false), (3, true)] {
+            let mut code = Bytecode::default();
+            for slot in 0..num_cleared_slots {
+                code.op_sstore(slot, 0);
+            }
+            code.op_stop();
+
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(eth(10))
+                        .code(code)
+                        .storage(
+                            (0..num_cleared_slots).map(|slot| (Word::from(slot), Word::one())),
+                        );
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                },
+                |mut txs, accs| {
+                    txs[0].to(accs[0].address).from(accs[1].address);
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            let tx_gas = ctx.eth_block.transactions[0].gas.as_u64();
+            let last_step = ctx.geth_traces[0].struct_logs.last().unwrap();
+            let gas_used = tx_gas - last_step.gas.0;
+            assert_eq!(
+                last_step.refund.0 > gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
+                is_capped
+            );
+
+            let mut ctb = CircuitTestBuilder::new_from_test_ctx(ctx).params(CircuitsParams {
+                max_txs: 5,
+                ..Default::default()
+            });
+            if cfg!(feature = "strict-refund-cap") && is_capped {
+                ctb = ctb.evm_checks(Some(Box::new(|prover, gate_rows, lookup_rows| {
+                    assert!(prover
+                        .verify_at_rows_par(gate_rows.iter().cloned(), lookup_rows.iter().cloned())
+                        .is_err())
+                })));
+            }
+            ctb.run();
+        }
+    }
+
     #[test]
     fn end_tx_gadget_nonexisting_coinbase() {
         test_ok(
@@ -11,7 +11,7 @@ use std::{cell::RefCell, collections::BTreeMap, iter, marker::PhantomData, str::
 
 use crate::{evm_circuit::util::constraint_builder::ConstrainBuilderCommon, table::KeccakTable};
 use bus_mapping::circuit_input_builder::get_dummy_tx_hash;
-use eth_types::{Address, Field, Hash, ToBigEndian, ToWord, Word, H256};
+use eth_types::{geth_types::TxType, Address, Field, Hash, ToBigEndian, ToWord, Word, H256};
 use ethers_core::utils::keccak256;
 use halo2_proofs::plonk::{Assigned, Expression, Fixed, Instance};
 
@@ -32,11 +32,16 @@ use crate::{
         RPI_LENGTH_ACC_CELL_IDX, RPI_RLC_ACC_CELL_IDX, TIMESTAMP_OFFSET,
     },
     state_circuit::StateCircuitExports,
-    tx_circuit::{CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, TX_HASH_OFFSET, TX_LEN},
+    tx_circuit::{
+        CHAIN_ID_OFFSET as CHAIN_ID_OFFSET_IN_TX, TX_HASH_OFFSET, TX_LEN, TX_TYPE_OFFSET,
+    },
     witness::{self, Block, BlockContext, BlockContexts, Transaction},
 };
 use bus_mapping::util::read_env_var;
-use gadgets::util::{and, not, select, Expr};
+use gadgets::{
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    util::{and, not, select, Expr},
+};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
@@ -355,6 +360,13 @@ pub struct PiCircuitConfig<F: Field> {
     is_block_num_txs: Column<Fixed>,
     q_block_tag: Column<Fixed>,
 
+    // columns for assertion about the chain_id of each tx in tx table
+    q_tx_chain_id: Selector,
+    chain_id: Column<Advice>,
+    tx_type: Column<Advice>,
+    tx_chain_id: Column<Advice>,
+    is_l1_msg: IsZeroConfig<F>,
+
     q_field_step: Selector,
     is_field_rlc: Column<Fixed>,
 
@@ -451,6 +463,19 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         let cum_num_txs = meta.advice_column();
         let is_block_num_txs = meta.fixed_column();
 
+        let q_tx_chain_id = meta.selector();
+        let chain_id = meta.advice_column_in(SecondPhase);
+        let tx_type = meta.advice_column_in(SecondPhase);
+        let tx_chain_id = meta.advice_column_in(SecondPhase);
+        // tx_type is at 2nd phase, so is the inverse of tx_type - L1Msg
+        let tx_type_diff_inv = meta.advice_column_in(SecondPhase);
+        let is_l1_msg = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_tx_chain_id),
+            |meta| meta.query_advice(tx_type, Rotation::cur()) - (TxType::L1Msg as u64).expr(),
+            tx_type_diff_inv,
+        );
+
         meta.enable_constant(constant);
         meta.enable_equality(rpi_bytes);
         meta.enable_equality(rpi_bytes_acc);
@@ -462,6 +487,9 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
         meta.enable_equality(block_table.index);
         meta.enable_equality(tx_table.value); // copy tx hashes to rpi
         meta.enable_equality(cum_num_txs);
+        meta.enable_equality(chain_id);
+        meta.enable_equality(tx_type);
+        meta.enable_equality(tx_chain_id);
         meta.enable_equality(pi);
 
         // 1. constrain rpi_bytes, rpi_bytes_acc, and rpi for each field
@@ -689,6 +717,24 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             }
         );
 
+        // 4. constrain the chain_id of each tx in tx table. L1 msgs have no chain_id, so the
+        // ChainID row of an L1 msg is 0 and the one of any other tx is the public chain_id.
+        meta.create_gate("tx_chain_id == is_l1_msg ? 0 : chain_id", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "tx_chain_id == is_l1_msg ? 0 : chain_id",
+                meta.query_advice(tx_chain_id, Rotation::cur()),
+                select::expr(
+                    is_l1_msg.expr(),
+                    0.expr(),
+                    meta.query_advice(chain_id, Rotation::cur()),
+                ),
+            );
+
+            cb.gate(meta.query_selector(q_tx_chain_id))
+        });
+
         Self {
             block_table,
             tx_table,
@@ -710,6 +756,11 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             cum_num_txs,
             q_block_tag,
             is_block_num_txs,
+            q_tx_chain_id,
+            chain_id,
+            tx_type,
+            tx_chain_id,
+            is_l1_msg,
             pi,
             _marker: PhantomData,
             q_block_context,
@@ -1070,13 +1121,9 @@ impl<F: Field> PiCircuitConfig<F> {
                 block_value_cells[block_idx * BLOCK_LEN + CHAIN_ID_OFFSET].cell(),
             )?;
         }
-        // copy chain_id to tx table
-        for tx_id in 0..public_data.max_txs {
-            region.constrain_equal(
-                rpi_cells[0].cell(),
-                tx_value_cells[tx_id * TX_LEN + CHAIN_ID_OFFSET_IN_TX - 1].cell(),
-            )?;
-        }
+        // copy chain_id to tx table, i.e. the chain_id of each L2 tx (and the chain_id that
+        // its signature is verified with) is the chain_id in the public input
+        self.assign_tx_chain_ids(region, public_data, &rpi_cells[0], tx_value_cells)?;
         // connections to be done with other sub-circuits.
         let connections = Connections {
             start_state_root: rpi_cells[1].clone(),
@@ -1128,6 +1175,44 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok((offset + 1, pi_hash_rlc_cell, connections))
     }
 
+    /// Assign the chain_id of each tx in tx table, i.e. 0 for L1 msgs and the public chain_id
+    /// for the other txs. The tx type and chain_id of each tx are copied from tx table, so
+    /// the copy constraints don't depend on the txs. These columns are unused elsewhere,
+    /// hence the rows from offset 0 on are taken.
+    fn assign_tx_chain_ids(
+        &self,
+        region: &mut Region<'_, F>,
+        public_data: &PublicData,
+        chain_id_cell: &AssignedCell<F, F>,
+        tx_value_cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        let is_l1_msg_chip = IsZeroChip::construct(self.is_l1_msg.clone());
+        for tx_id in 0..public_data.max_txs {
+            let offset = tx_id;
+            self.q_tx_chain_id.enable(region, offset)?;
+            chain_id_cell.copy_advice(|| "chain_id", region, self.chain_id, offset)?;
+            let tx_type = tx_value_cells[tx_id * TX_LEN + TX_TYPE_OFFSET - 1].copy_advice(
+                || "tx_type",
+                region,
+                self.tx_type,
+                offset,
+            )?;
+            tx_value_cells[tx_id * TX_LEN + CHAIN_ID_OFFSET_IN_TX - 1].copy_advice(
+                || "tx_chain_id",
+                region,
+                self.tx_chain_id,
+                offset,
+            )?;
+            is_l1_msg_chip.assign(
+                region,
+                offset,
+                tx_type.value().copied() - Value::known(F::from(TxType::L1Msg as u64)),
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Assign the (hi, lo) decomposition of pi_hash.
     fn assign_pi_hash(
         &self,
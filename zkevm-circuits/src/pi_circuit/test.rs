@@ -150,3 +150,38 @@ fn variadic_size_check() {
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_0, block_2.clone()]);
     run_size_check::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>([block_1, block_2]);
 }
+
+#[cfg(feature = "scroll")]
+#[test]
+fn serial_test_pi_tx_chain_id_mismatch() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 20;
+    const MAX_INNER_BLOCKS: usize = 4;
+
+    let mut difficulty_be_bytes = [0u8; 32];
+    MOCK_DIFFICULTY.to_big_endian(&mut difficulty_be_bytes);
+    set_var("DIFFICULTY", hex::encode(difficulty_be_bytes));
+    set_var("COINBASE", "0x0000000000000000000000000000000000000000");
+
+    // the tx claims another chain id than the one in the public input
+    let mut block = block_1tx();
+    block.txs[0].chain_id = block.chain_id + 1;
+
+    let circuit = PiTestCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS>(PiCircuit::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        MAX_INNER_BLOCKS,
+        &block,
+    ));
+    let public_inputs = circuit.0.instance();
+    let prover = MockProver::run(16, &circuit, public_inputs).unwrap();
+    let errors = prover.verify().expect_err("the chain ids differ");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if format!("{constraint}").contains("tx_chain_id == is_l1_msg ? 0 : chain_id")
+        )),
+        "{errors:?}"
+    );
+}
@@ -72,8 +72,10 @@ pub struct StateCircuitConfig<F> {
     state_root: Column<Advice>,
     lexicographic_ordering: LexicographicOrderingConfig,
     not_first_access: Column<Advice>,
+    // u16 limbs of the value of Rw::TxRefund rows, which must fit into u64
+    tx_refund_limbs: [Column<Advice>; N_LIMBS_TX_REFUND],
     lookups: LookupsConfig,
-    power_of_randomness: [Expression<F>; N_BYTES_WORD - 1],
+    power_of_randomness: [Expression<F>; N_BYTES_WORD],
     // External tables
     mpt_table: MptTable,
 }
@@ -112,7 +114,8 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
         let selector = rw_table.q_enable;
         log::debug!("state circuit selector {:?}", selector);
         let lookups = LookupsChip::configure(meta);
-        let power_of_randomness: [Expression<F>; 31] = challenges.evm_word_powers_of_randomness();
+        let power_of_randomness: [Expression<F>; N_BYTES_WORD] =
+            challenges.evm_word_powers_of_randomness();
 
         let rw_counter = MpiChip::configure(meta, selector, rw_table.rw_counter, lookups);
         let tag = BinaryNumberChip::configure(meta, selector, Some(rw_table.tag.into()));
@@ -164,7 +167,7 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             meta,
             sort_keys,
             lookups,
-            power_of_randomness.clone(),
+            challenges.evm_word_powers_of_randomness(),
         );
 
         // annotate columns
@@ -180,6 +183,7 @@ impl<F: Field> SubCircuitConfig<F> for StateCircuitConfig<F> {
             state_root,
             lexicographic_ordering,
             not_first_access: meta.advice_column(),
+            tx_refund_limbs: [(); N_LIMBS_TX_REFUND].map(|_| meta.advice_column()),
             lookups,
             power_of_randomness,
             rw_table,
@@ -283,6 +287,10 @@ impl<F: Field> StateCircuitConfig<F> {
                     .assign(region, offset, randomness, storage_key)?;
             }
 
+            if let Rw::TxRefund { value, .. } = row {
+                self.assign_tx_refund_limbs(region, offset, *value)?;
+            }
+
             if offset > 0 {
                 let prev_row = &rows[offset - 1];
                 let index = self
@@ -475,6 +483,10 @@ impl<F: Field> StateCircuitConfig<F> {
                     .assign(region, offset, randomness, storage_key)?;
             }
 
+            if let Rw::TxRefund { value, .. } = row {
+                self.assign_tx_refund_limbs(region, offset, *value)?;
+            }
+
             if idx > 0 {
                 let prev_row = &rows[idx - 1];
                 let index = self
@@ -819,6 +831,23 @@ impl<F: Field> StateCircuitConfig<F> {
         )
     }
 
+    fn assign_tx_refund_limbs(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: u64,
+    ) -> Result<(), Error> {
+        for (i, limb) in self.tx_refund_limbs.iter().enumerate() {
+            region.assign_advice(
+                || format!("limb[{i}] of tx refund"),
+                *limb,
+                offset,
+                || Value::known(F::from((value >> (16 * i)) & 0xffff)),
+            )?;
+        }
+        Ok(())
+    }
+
     fn annotate_circuit_in_region(&self, region: &mut Region<F>) {
         self.rw_table.annotate_columns_in_region(region);
         self.mpt_table.annotate_columns_in_region(region);
@@ -829,6 +858,9 @@ impl<F: Field> StateCircuitConfig<F> {
         self.sort_keys.annotate_columns_in_region(region, "STATE");
         region.name_column(|| "STATE_selector", self.selector);
         region.name_column(|| "STATE_not_first_access", self.not_first_access);
+        for (i, limb) in self.tx_refund_limbs.iter().enumerate() {
+            region.name_column(|| format!("STATE_tx_refund_limb_{i}"), *limb);
+        }
         region.name_column(|| "STATE_phase2_initial_value", self.initial_value);
         region.name_column(|| "STATE_phase2_mpt_proof_type", self.mpt_proof_type);
         region.name_column(|| "STATE_phase2_state_root", self.state_root);
@@ -1046,6 +1078,7 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
             prev_rw_counter: meta.query_advice(c.rw_table.rw_counter, Rotation::prev()),
             is_write: meta.query_advice(c.rw_table.is_write, Rotation::cur()),
             tag: meta.query_advice(c.rw_table.tag, Rotation::cur()),
+            prev_tag: meta.query_advice(c.rw_table.tag, Rotation::prev()),
             id: meta.query_advice(c.rw_table.id, Rotation::cur()),
             prev_id: meta.query_advice(c.rw_table.id, Rotation::prev()),
             address: meta.query_advice(c.rw_table.address, Rotation::cur()),
@@ -1095,6 +1128,9 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
         first_different_limb: [0, 1, 2, 3]
             .map(|idx| meta.query_advice(first_different_limb.bits[idx], Rotation::cur())),
         not_first_access: meta.query_advice(c.not_first_access, Rotation::cur()),
+        tx_refund_limbs: c
+            .tx_refund_limbs
+            .map(|limb| meta.query_advice(limb, Rotation::cur())),
         last_access: 1.expr() - meta.query_advice(c.not_first_access, Rotation::next()),
         state_root: meta.query_advice(c.state_root, Rotation::cur()),
         state_root_prev: meta.query_advice(c.state_root, Rotation::prev()),
@@ -18,6 +18,7 @@ pub struct RwTableQueries<F: Field> {
     pub prev_rw_counter: Expression<F>,
     pub is_write: Expression<F>,
     pub tag: Expression<F>,
+    pub prev_tag: Expression<F>,
     pub id: Expression<F>,
     pub prev_id: Expression<F>,
     pub address: Expression<F>,
@@ -42,8 +43,11 @@ pub struct MptUpdateTableQueries<F: Field> {
     pub old_value: Expression<F>,
 }
 
+/// Queries used by the [`ConstraintBuilder`]. `N_BYTES_STORAGE_KEY` is the
+/// number of bytes the storage key is decomposed into for its RLC, which
+/// defaults to an EVM word.
 #[derive(Clone)]
-pub struct Queries<F: Field> {
+pub struct Queries<F: Field, const N_BYTES_STORAGE_KEY: usize = N_BYTES_WORD> {
     pub selector: Expression<F>,
     pub rw_table: RwTableQueries<F>,
     pub mpt_update_table: MptUpdateTableQueries<F>,
@@ -53,15 +57,17 @@ pub struct Queries<F: Field> {
     pub id: MpiQueries<F, N_LIMBS_ID>,
     pub is_tag_and_id_unchanged: Expression<F>,
     pub address: MpiQueries<F, N_LIMBS_ACCOUNT_ADDRESS>,
-    pub storage_key: RlcQueries<F, N_BYTES_WORD>,
+    pub storage_key: RlcQueries<F, N_BYTES_STORAGE_KEY>,
     pub initial_value: Expression<F>,
     pub initial_value_prev: Expression<F>,
     pub is_non_exist: Expression<F>,
     pub mpt_proof_type: Expression<F>,
     pub lookups: LookupsQueries<F>,
-    pub power_of_randomness: [Expression<F>; N_BYTES_WORD - 1],
+    // powers of randomness r^1, ..., r^N_BYTES_STORAGE_KEY
+    pub power_of_randomness: [Expression<F>; N_BYTES_STORAGE_KEY],
     pub first_different_limb: [Expression<F>; 4],
     pub not_first_access: Expression<F>,
+    pub tx_refund_limbs: [Expression<F>; N_LIMBS_TX_REFUND],
     pub last_access: Expression<F>,
     pub state_root: Expression<F>,
     pub state_root_prev: Expression<F>,
@@ -70,18 +76,23 @@ pub struct Queries<F: Field> {
 type Constraint<F> = (&'static str, Expression<F>);
 type Lookup<F> = (&'static str, Vec<(Expression<F>, Expression<F>)>);
 
-pub struct ConstraintBuilder<F: Field> {
+pub struct ConstraintBuilder<F: Field, const N_BYTES_STORAGE_KEY: usize = N_BYTES_WORD> {
     pub constraints: Vec<Constraint<F>>,
     lookups: Vec<Lookup<F>>,
+    // name of each lookup and the tag whose constraints added it
+    lookup_report: Vec<(&'static str, RwTableTag)>,
     condition: Expression<F>,
+    tag: Option<RwTableTag>,
 }
 
-impl<F: Field> ConstraintBuilder<F> {
+impl<F: Field, const N_BYTES_STORAGE_KEY: usize> ConstraintBuilder<F, N_BYTES_STORAGE_KEY> {
     pub fn new() -> Self {
         Self {
             constraints: vec![],
             lookups: vec![],
+            lookup_report: vec![],
             condition: 1.expr(),
+            tag: None,
         }
     }
 
@@ -97,42 +108,37 @@ impl<F: Field> ConstraintBuilder<F> {
         self.lookups.clone()
     }
 
-    pub fn build(&mut self, q: &Queries<F>) {
+    /// Returns the name of each lookup with the tag whose constraints added it, e.g. to
+    /// check that no tag misses its range checks.
+    pub fn lookup_report(&self) -> Vec<(&'static str, RwTableTag)> {
+        self.lookup_report.clone()
+    }
+
+    pub fn build(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         self.build_general_constraints(q);
-        self.condition(q.tag_matches(RwTableTag::Start), |cb| {
-            cb.build_start_constraints(q)
-        });
-        self.condition(q.tag_matches(RwTableTag::Memory), |cb| {
-            cb.build_memory_constraints(q)
-        });
-        self.condition(q.tag_matches(RwTableTag::Stack), |cb| {
-            cb.build_stack_constraints(q)
-        });
-        self.condition(q.tag_matches(RwTableTag::AccountStorage), |cb| {
+        self.tag_condition(q, RwTableTag::Start, |cb| cb.build_start_constraints(q));
+        self.tag_condition(q, RwTableTag::Memory, |cb| cb.build_memory_constraints(q));
+        self.tag_condition(q, RwTableTag::Stack, |cb| cb.build_stack_constraints(q));
+        self.tag_condition(q, RwTableTag::AccountStorage, |cb| {
             cb.build_account_storage_constraints(q)
         });
-        self.condition(q.tag_matches(RwTableTag::TxAccessListAccount), |cb| {
+        self.tag_condition(q, RwTableTag::TxAccessListAccount, |cb| {
             cb.build_tx_access_list_account_constraints(q)
         });
-        self.condition(
-            q.tag_matches(RwTableTag::TxAccessListAccountStorage),
-            |cb| cb.build_tx_access_list_account_storage_constraints(q),
-        );
-        self.condition(q.tag_matches(RwTableTag::TxRefund), |cb| {
-            cb.build_tx_refund_constraints(q)
+        self.tag_condition(q, RwTableTag::TxAccessListAccountStorage, |cb| {
+            cb.build_tx_access_list_account_storage_constraints(q)
         });
-        self.condition(q.tag_matches(RwTableTag::Account), |cb| {
-            cb.build_account_constraints(q)
+        self.tag_condition(q, RwTableTag::TxRefund, |cb| {
+            cb.build_tx_refund_constraints(q)
         });
-        self.condition(q.tag_matches(RwTableTag::CallContext), |cb| {
+        self.tag_condition(q, RwTableTag::Account, |cb| cb.build_account_constraints(q));
+        self.tag_condition(q, RwTableTag::CallContext, |cb| {
             cb.build_call_context_constraints(q)
         });
-        self.condition(q.tag_matches(RwTableTag::TxLog), |cb| {
-            cb.build_tx_log_constraints(q)
-        });
+        self.tag_condition(q, RwTableTag::TxLog, |cb| cb.build_tx_log_constraints(q));
     }
 
-    fn build_general_constraints(&mut self, q: &Queries<F>) {
+    fn build_general_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // tag value in RwTableTag range is enforced in BinaryNumberChip
         self.require_boolean("is_write is boolean", q.is_write());
 
@@ -174,7 +180,7 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
-    fn build_start_constraints(&mut self, q: &Queries<F>) {
+    fn build_start_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // 1.0. Unused keys are 0
         self.require_zero("field_tag is 0 for Start", q.field_tag());
         self.require_zero("address is 0 for Start", q.rw_table.address.clone());
@@ -198,9 +204,18 @@ impl<F: Field> ConstraintBuilder<F> {
             )
         });
         self.require_zero("value_prev column is 0 for Start", q.value_prev_column());
+        // 1.5. Start rows are a prefix of the rw table, so a Start row can only follow
+        // another Start row
+        self.condition(q.lexicographic_ordering_selector.clone(), |cb| {
+            cb.require_equal(
+                "Start rows only appear at the beginning",
+                q.tag_prev(),
+                (RwTableTag::Start as u64).expr(),
+            )
+        });
     }
 
-    fn build_memory_constraints(&mut self, q: &Queries<F>) {
+    fn build_memory_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // 2.0. Unused keys are 0
         self.require_zero("field_tag is 0 for Memory", q.field_tag());
         self.require_zero(
@@ -243,9 +258,12 @@ impl<F: Field> ConstraintBuilder<F> {
                 q.value_prev_column(),
             );
         });
+        // 2.7. Within a call, memory rows are sorted by address and then by rw_counter. This
+        // is enforced by the lexicographic ordering of the sort keys (tag, id, address,
+        // rw_counter), whose first non-zero limb difference must fit into u16.
     }
 
-    fn build_stack_constraints(&mut self, q: &Queries<F>) {
+    fn build_stack_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // 3.0. Unused keys are 0
         self.require_zero("field_tag is 0 for Stack", q.field_tag());
         self.require_zero("storage_key is 0 for Stack", q.rw_table.storage_key.clone());
@@ -281,7 +299,7 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
-    fn build_account_storage_constraints(&mut self, q: &Queries<F>) {
+    fn build_account_storage_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // TODO: cold VS warm
         // ref. spec 4.0. Unused keys are 0
         // See comment above configure for is_non_exist in state_circuit.rs for a explanation of why
@@ -324,6 +342,9 @@ impl<F: Field> ConstraintBuilder<F> {
                 ],
             );
         });
+        // ref. spec 4.2. The committed value (initial_value) is the value before the block and
+        // stays the same across all accesses to (address, storage_key). This is enforced by
+        // "initial value doesn't change in an access group" in the general constraints.
 
         self.condition(q.not_first_access.clone(), |cb| {
             cb.require_equal(
@@ -334,7 +355,7 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
-    fn build_tx_access_list_account_constraints(&mut self, q: &Queries<F>) {
+    fn build_tx_access_list_account_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         self.require_zero("field_tag is 0 for TxAccessListAccount", q.field_tag());
         self.require_zero(
             "storage_key is 0 for TxAccessListAccount",
@@ -361,7 +382,10 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
-    fn build_tx_access_list_account_storage_constraints(&mut self, q: &Queries<F>) {
+    fn build_tx_access_list_account_storage_constraints(
+        &mut self,
+        q: &Queries<F, N_BYTES_STORAGE_KEY>,
+    ) {
         self.require_zero(
             "field_tag is 0 for TxAccessListAccountStorage",
             q.field_tag(),
@@ -387,7 +411,7 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
-    fn build_tx_refund_constraints(&mut self, q: &Queries<F>) {
+    fn build_tx_refund_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // 7.0. `address`, `field_tag` and `storage_key` are 0
         self.require_zero("address is 0 for TxRefund", q.rw_table.address.clone());
         self.require_zero("field_tag is 0 for TxRefund", q.field_tag());
@@ -411,9 +435,23 @@ impl<F: Field> ConstraintBuilder<F> {
         });
         // 7.2. `initial value` is 0
         self.require_zero("initial TxRefund value is 0", q.initial_value());
+        // 7.3. `value` fits into u64, i.e. 4 u16 limbs
+        self.require_equal(
+            "TxRefund value is composed of its u16 limbs",
+            q.rw_table.value.clone(),
+            q.tx_refund_limbs.iter().rev().fold(0.expr(), |acc, limb| {
+                acc * (1u64 << 16).expr() + limb.clone()
+            }),
+        );
+        for limb in q.tx_refund_limbs.iter() {
+            self.add_lookup(
+                "TxRefund value limb fits into u16",
+                vec![(limb.clone(), q.lookups.u16.clone())],
+            );
+        }
     }
 
-    fn build_account_constraints(&mut self, q: &Queries<F>) {
+    fn build_account_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // ref. spec 6.0. Unused keys are 0
         self.require_zero("id is 0 for Account", q.id());
         self.require_zero(
@@ -467,7 +505,7 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
-    fn build_call_context_constraints(&mut self, q: &Queries<F>) {
+    fn build_call_context_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         self.require_zero("address is 0 for CallContext", q.rw_table.address.clone());
         self.require_zero(
             "storage_key is 0 for CallContext",
@@ -477,6 +515,16 @@ impl<F: Field> ConstraintBuilder<F> {
             "field_tag in CallContextFieldTag range",
             vec![(q.field_tag(), q.lookups.call_context_field_tag.clone())],
         );
+        self.add_lookup(
+            "read-only CallContext field is only written on first access",
+            vec![
+                (q.field_tag(), q.lookups.call_context_field_tag.clone()),
+                (
+                    q.not_first_access() * q.is_write(),
+                    q.lookups.call_context_field_rewritable.clone(),
+                ),
+            ],
+        );
         self.require_zero("initial CallContext value is 0", q.initial_value());
         self.require_equal(
             "state_root is unchanged for CallContext",
@@ -489,7 +537,7 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
-    fn build_tx_log_constraints(&mut self, q: &Queries<F>) {
+    fn build_tx_log_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         self.require_equal(
             "is_write is always true for TxLog",
             q.rw_table.is_write.clone(),
@@ -509,7 +557,7 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
-    fn build_tx_receipt_constraints(&mut self, q: &Queries<F>) {
+    fn build_tx_receipt_constraints(&mut self, q: &Queries<F, N_BYTES_STORAGE_KEY>) {
         // TODO: implement TxReceipt constraints
         self.require_equal("TxReceipt rows not implemented", 1.expr(), 0.expr());
 
@@ -551,6 +599,21 @@ impl<F: Field> ConstraintBuilder<F> {
             *expression = expression.clone() * self.condition.clone();
         }
         self.lookups.push((name, lookup));
+        self.lookup_report.push((
+            name,
+            self.tag.expect("lookups are added under a tag condition"),
+        ));
+    }
+
+    fn tag_condition(
+        &mut self,
+        q: &Queries<F, N_BYTES_STORAGE_KEY>,
+        tag: RwTableTag,
+        build: impl FnOnce(&mut Self),
+    ) {
+        let original_tag = self.tag.replace(tag);
+        self.condition(q.tag_matches(tag), build);
+        self.tag = original_tag;
     }
 
     fn condition(&mut self, condition: Expression<F>, build: impl FnOnce(&mut Self)) {
@@ -561,7 +624,7 @@ impl<F: Field> ConstraintBuilder<F> {
     }
 }
 
-impl<F: Field> Queries<F> {
+impl<F: Field, const N_BYTES_STORAGE_KEY: usize> Queries<F, N_BYTES_STORAGE_KEY> {
     fn selector(&self) -> Expression<F> {
         self.selector.clone()
     }
@@ -578,6 +641,10 @@ impl<F: Field> Queries<F> {
         self.rw_table.tag.clone()
     }
 
+    fn tag_prev(&self) -> Expression<F> {
+        self.rw_table.prev_tag.clone()
+    }
+
     fn id(&self) -> Expression<F> {
         self.rw_table.id.clone()
     }
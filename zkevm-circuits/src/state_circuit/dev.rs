@@ -89,6 +89,7 @@ pub enum AdviceColumn {
     // NonEmptyWitness is the BatchedIsZero chip witness that contains the
     // inverse of the non-zero value if any in [committed_value, value]
     NonEmptyWitness,
+    TxRefundLimb3, // most significant limb
 }
 
 impl AdviceColumn {
@@ -119,6 +120,7 @@ impl AdviceColumn {
             Self::InitialValue => config.initial_value,
             Self::IsZero => config.is_non_exist.is_zero,
             Self::NonEmptyWitness => config.is_non_exist.nonempty_witness,
+            Self::TxRefundLimb3 => config.tx_refund_limbs[3],
         }
     }
 }
@@ -16,6 +16,9 @@ pub struct Config {
     u10: Column<Fixed>,
     u16: Column<Fixed>,
     pub call_context_field_tag: Column<Fixed>,
+    // 1 on the extra rows of call_context_field_tag holding the fields that can be written
+    // again after the call context is created
+    pub call_context_field_rewritable: Column<Fixed>,
 }
 
 impl Config {
@@ -60,6 +63,7 @@ pub struct Queries<F> {
     pub u10: Expression<F>,
     pub u16: Expression<F>,
     pub call_context_field_tag: Expression<F>,
+    pub call_context_field_rewritable: Expression<F>,
 }
 
 impl<F: Field> Queries<F> {
@@ -69,6 +73,8 @@ impl<F: Field> Queries<F> {
             u10: meta.query_fixed(c.u10, Rotation::cur()),
             u16: meta.query_fixed(c.u16, Rotation::cur()),
             call_context_field_tag: meta.query_fixed(c.call_context_field_tag, Rotation::cur()),
+            call_context_field_rewritable: meta
+                .query_fixed(c.call_context_field_rewritable, Rotation::cur()),
         }
     }
 }
@@ -92,6 +98,7 @@ impl<F: Field> Chip<F> {
             u10: meta.fixed_column(),
             u16: meta.fixed_column(),
             call_context_field_tag: meta.fixed_column(),
+            call_context_field_rewritable: meta.fixed_column(),
         };
         meta.annotate_lookup_any_column(config.u8, || "LOOKUP_u8");
         meta.annotate_lookup_any_column(config.u10, || "LOOKUP_u10");
@@ -99,6 +106,9 @@ impl<F: Field> Chip<F> {
         meta.annotate_lookup_any_column(config.call_context_field_tag, || {
             "LOOKUP_call_context_field_tag"
         });
+        meta.annotate_lookup_any_column(config.call_context_field_rewritable, || {
+            "LOOKUP_call_context_field_rewritable"
+        });
         config
     }
 
@@ -134,6 +144,25 @@ impl<F: Field> Chip<F> {
                         || Value::known(F::from(field_tag as u64)),
                     )?;
                 }
+                // (field_tag, 1) rows for the fields that are not read-only
+                let offset = CallContextFieldTag::iter().count() + 1;
+                for (i, field_tag) in CallContextFieldTag::iter()
+                    .filter(|field_tag| !field_tag.is_read_only())
+                    .enumerate()
+                {
+                    region.assign_fixed(
+                        || format!("assign {field_tag:?} in call_context_field_tag fixed column"),
+                        self.config.call_context_field_tag,
+                        offset + i,
+                        || Value::known(F::from(field_tag as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || format!("assign {field_tag:?} in call_context_field_rewritable fixed column"),
+                        self.config.call_context_field_rewritable,
+                        offset + i,
+                        || Value::known(F::one()),
+                    )?;
+                }
                 Ok(())
             },
         )?;
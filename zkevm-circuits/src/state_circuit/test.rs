@@ -1,6 +1,7 @@
 #![allow(unused_imports)]
 pub use super::{dev::*, *};
 use crate::{
+    evm_circuit::param::N_BYTES_WORD,
     table::{AccountFieldTag, CallContextFieldTag, RwTableTag, TxLogFieldTag, TxReceiptFieldTag},
     util::{unusable_rows, SubCircuit},
     witness::{MptUpdates, Rw, RwMap},
@@ -18,8 +19,11 @@ use halo2_proofs::{
     arithmetic::Field as Halo2Field,
     circuit::SimpleFloorPlanner,
     dev::{MockProver, VerifyFailure},
-    halo2curves::bn256::{Bn256, Fr},
-    plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem},
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        group::ff::PrimeField,
+    },
+    plonk::{keygen_vk, Advice, Circuit, Column, ConstraintSystem, Expression},
     poly::kzg::commitment::ParamsKZG,
 };
 use rand::SeedableRng;
@@ -63,6 +67,43 @@ fn degree() {
     assert_eq!(meta.degree(), 9);
 }
 
+#[test]
+fn lookup_report() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = StateCircuit::<Fr>::configure(&mut meta);
+    let mut report = vec![];
+    meta.create_gate("lookup report", |meta| {
+        let q = super::queries(meta, &config);
+        let mut cb = super::constraint_builder::ConstraintBuilder::new();
+        cb.build(&q);
+        report = cb.lookup_report();
+        vec![q.selector]
+    });
+
+    for lookup in [
+        ("limb fits into u16", RwTableTag::Memory),
+        ("stack address fits into 10 bits", RwTableTag::Stack),
+    ] {
+        assert!(
+            report.contains(&lookup),
+            "{lookup:?} is missing in {report:?}"
+        );
+    }
+}
+
+#[test]
+fn storage_key_rlc_queries_length() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = StateCircuit::<Fr>::configure(&mut meta);
+    meta.create_gate("storage key rlc queries", |meta| {
+        let q = super::queries(meta, &config);
+        // fails to compile if the default storage key length changes
+        let _: &[Expression<Fr>; N_BYTES_WORD] = &q.storage_key.bytes;
+        let _: &[Expression<Fr>; N_BYTES_WORD] = &q.power_of_randomness;
+        vec![q.selector]
+    });
+}
+
 #[test]
 fn verifying_key_independent_of_rw_length() {
     let params = ParamsKZG::<Bn256>::setup(17, rand_chacha::ChaCha20Rng::seed_from_u64(2));
@@ -198,6 +239,67 @@ fn state_circuit_simple_6() {
     test_state_circuit_ok(vec![memory_op_0, memory_op_1], vec![], vec![storage_op_2]);
 }
 
+#[test]
+fn rw_rlc_distinct() {
+    let memory_ops = vec![
+        Operation::new(
+            RWCounter::from(12),
+            RW::WRITE,
+            MemoryOp::new_write(1, MemoryAddress::from(0), 32.into(), 0.into()),
+        ),
+        Operation::new(
+            RWCounter::from(13),
+            RW::READ,
+            MemoryOp::new_write(1, MemoryAddress::from(0), 32.into(), 32.into()),
+        ),
+    ];
+    let stack_ops = vec![
+        Operation::new(
+            RWCounter::from(14),
+            RW::WRITE,
+            StackOp::new(1, StackAddress::from(1), Word::from(32)),
+        ),
+        Operation::new(
+            RWCounter::from(15),
+            RW::READ,
+            StackOp::new(1, StackAddress::from(1), Word::from(32)),
+        ),
+    ];
+    let storage_ops = vec![Operation::new(
+        RWCounter::from(16),
+        RW::WRITE,
+        StorageOp::new(
+            U256::from(100).to_address(),
+            Word::from(0x40),
+            Word::from(32),
+            Word::zero(),
+            1usize,
+            Word::zero(),
+        ),
+    )];
+    let rw_map = RwMap::from(&OperationContainer {
+        memory: memory_ops,
+        stack: stack_ops,
+        storage: storage_ops,
+        ..Default::default()
+    });
+    let (rows, _) = RwMap::table_assignments_prepad(&rw_map.table_assignments(), 64);
+
+    // the evm circuit looks up the rw table by the rlc of the whole row, so distinct
+    // rows must not share it
+    let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(2);
+    let (evm_word, lookup_input) = (Fr::random(&mut rng), Fr::random(&mut rng));
+    let rlcs: BTreeSet<_> = rows
+        .iter()
+        .map(|row| {
+            row.table_assignment_aux(evm_word)
+                .rlc(lookup_input)
+                .to_repr()
+        })
+        .collect();
+    assert_eq!(rlcs.len(), rows.len());
+}
+
 #[test]
 fn lexicographic_ordering_test_1() {
     let memory_op = Operation::new(
@@ -471,6 +573,96 @@ fn is_write_nonbinary() {
     assert_error_matches(result, "is_write is boolean");
 }
 
+#[test]
+fn call_context_read_only_field_written_once() {
+    let rows = vec![
+        Rw::CallContext {
+            rw_counter: 1,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::Depth,
+            value: U256::from(2),
+        },
+        Rw::CallContext {
+            rw_counter: 2,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::Depth,
+            value: U256::from(2),
+        },
+        Rw::CallContext {
+            rw_counter: 3,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::IsSuccess,
+            value: U256::one(),
+        },
+        Rw::CallContext {
+            rw_counter: 4,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::IsSuccess,
+            value: U256::zero(),
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn call_context_read_only_field_rewritten() {
+    let rows = vec![
+        Rw::CallContext {
+            rw_counter: 1,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::Depth,
+            value: U256::from(2),
+        },
+        Rw::CallContext {
+            rw_counter: 2,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::Depth,
+            value: U256::from(3),
+        },
+    ];
+
+    assert_error_matches(
+        verify(rows),
+        "read-only CallContext field is only written on first access",
+    );
+}
+
+#[test]
+fn call_context_read_only_field_changed_mid_call() {
+    let rows = vec![
+        Rw::CallContext {
+            rw_counter: 1,
+            is_write: true,
+            call_id: 1,
+            field_tag: CallContextFieldTag::CallerAddress,
+            value: U256::from(0x1234),
+        },
+        Rw::CallContext {
+            rw_counter: 2,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::CallerAddress,
+            value: U256::from(0x1234),
+        },
+        Rw::CallContext {
+            rw_counter: 3,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::CallerAddress,
+            value: U256::from(0x5678),
+        },
+    ];
+
+    assert_error_matches(verify(rows), "non-first access reads don't change value");
+}
+
 #[test]
 fn nonlexicographic_order_tag() {
     let first = Rw::Memory {
@@ -669,6 +861,29 @@ fn lexicographic_ordering_previous_limb_differences_nonzero() {
     );
 }
 
+#[test]
+fn memory_rows_out_of_order_within_call() {
+    let first = Rw::Memory {
+        rw_counter: 1,
+        is_write: true,
+        call_id: 1,
+        memory_address: 32,
+        value: 1.into(),
+        value_prev: 0.into(),
+    };
+    let second = Rw::Memory {
+        rw_counter: 2,
+        is_write: true,
+        call_id: 1,
+        memory_address: 64,
+        value: 2.into(),
+        value_prev: 0.into(),
+    };
+
+    assert_eq!(verify(vec![first, second]), Ok(()));
+    assert_error_matches(verify(vec![second, first]), "limb fits into u16");
+}
+
 #[test]
 fn read_inconsistency() {
     // memory word checking read_inconsistency
@@ -875,6 +1090,96 @@ fn invalid_tags() {
     }
 }
 
+#[test]
+fn account_committed_value_from_mpt() {
+    let balance_rows = |prior_balance: u64| {
+        vec![
+            Rw::Account {
+                rw_counter: 1,
+                is_write: false,
+                account_address: address!("0x00000000000000000000000000000000000cafe1"),
+                field_tag: AccountFieldTag::Balance,
+                value: U256::from(prior_balance),
+                value_prev: U256::from(prior_balance),
+            },
+            Rw::Account {
+                rw_counter: 2,
+                is_write: true,
+                account_address: address!("0x00000000000000000000000000000000000cafe1"),
+                field_tag: AccountFieldTag::Balance,
+                value: U256::from(150),
+                value_prev: U256::from(prior_balance),
+            },
+        ]
+    };
+    let rows = balance_rows(100);
+    assert_eq!(verify(rows.clone()), Ok(()));
+
+    // the first access reads a balance which is not the one before the block in the MPT
+    let circuit = StateCircuit::<Fr> {
+        rows: rows.clone(),
+        updates: MptUpdates::mock_from(&balance_rows(90)),
+        overrides: HashMap::new(),
+        n_rows: N_ROWS,
+        exports: Default::default(),
+        _marker: std::marker::PhantomData,
+    };
+    let instance = circuit.instance();
+    let result = MockProver::<Fr>::run(17, &circuit, instance)
+        .unwrap()
+        .verify_at_rows(N_ROWS - rows.len()..N_ROWS, N_ROWS - rows.len()..N_ROWS);
+    let errors = result.expect_err("the prior balance doesn't match the MPT");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. }
+                if name == "mpt_update exists in mpt circuit for Account last access"
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn account_storage_committed_value_changes_in_access_group() {
+    let storage_row = |rw_counter, value: u64, value_prev: u64| Rw::AccountStorage {
+        rw_counter,
+        is_write: true,
+        account_address: Address::default(),
+        storage_key: U256::from(6),
+        value: U256::from(value),
+        value_prev: U256::from(value_prev),
+        tx_id: 4,
+        committed_value: U256::from(5),
+    };
+    let rows = vec![
+        storage_row(1, 7, 5),
+        storage_row(2, 8, 7),
+        storage_row(3, 9, 8),
+    ];
+    assert_eq!(verify(rows.clone()), Ok(()));
+
+    // change the committed value of the middle row only, keeping the is_non_exist witness
+    // consistent with the new value.
+    let v = Fr::from(6);
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValue, 1), v),
+        ((AdviceColumn::NonEmptyWitness, 1), v.invert().unwrap()),
+    ]);
+
+    // the group changes at both the middle and the last row.
+    let errors = verify_with_overrides(rows, overrides).expect_err("result is not an error");
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    for error in errors {
+        match error {
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. } => {
+                assert!(format!("{constraint}")
+                    .contains("initial value doesn't change in an access group"))
+            }
+            _ => panic!("unexpected failure {error:?}"),
+        }
+    }
+}
+
 #[test]
 fn bad_initial_stack_value() {
     let rows = vec![Rw::Stack {
@@ -943,6 +1248,53 @@ fn bad_initial_tx_refund_value() {
     );
 }
 
+#[test]
+fn start_row_in_the_middle() {
+    let rows = vec![
+        Rw::Stack {
+            rw_counter: 1,
+            is_write: true,
+            call_id: 1,
+            stack_pointer: 1023,
+            value: U256::from(394500u64),
+        },
+        Rw::Start { rw_counter: 2 },
+    ];
+
+    // the lexicographic ordering is violated too, so only check that the Start row is
+    // rejected among the other failures.
+    let errors = verify(rows).expect_err("result is not an error");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if format!("{constraint}").contains("Start rows only appear at the beginning")
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_refund_value_out_of_range() {
+    let rows = vec![Rw::TxRefund {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        value: 20,
+        value_prev: 0,
+    }];
+    // 2^64 is composed of limbs [0, 0, 0, 2^16], but 2^16 doesn't fit into u16
+    let overrides = HashMap::from([
+        ((AdviceColumn::Value, 0), Fr::from(u64::MAX) + Fr::one()),
+        ((AdviceColumn::TxRefundLimb3, 0), Fr::from(1 << 16)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "TxRefund value limb fits into u16",
+    );
+}
+
 #[test]
 fn bad_initial_tx_log_value() {
     let rows = vec![Rw::TxLog {
@@ -1052,6 +1404,168 @@ fn bad_initial_tx_receipt_value() {
     );
 }
 
+/// Seeds for the randomized rw trace tests, so that any failure can be reproduced
+/// by rerunning with the same seed.
+const RW_TRACE_SEEDS: [u64; 3] = [0x5eed, 0xcafe, 0xdeadbeef];
+
+/// A random operation on one of a few Memory/Stack/Account/AccountStorage keys:
+/// (tag index, key index, is_write, written value).
+type RandomRwOp = (usize, usize, bool, u64);
+
+fn rw_trace_runner(seed: u64) -> proptest::test_runner::TestRunner {
+    use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+    let mut seed_bytes = [0u8; 32];
+    seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    TestRunner::new_with_rng(
+        Config {
+            cases: 4,
+            failure_persistence: None,
+            ..Config::default()
+        },
+        TestRng::from_seed(RngAlgorithm::ChaCha, &seed_bytes),
+    )
+}
+
+fn rw_trace_strategy() -> impl proptest::strategy::Strategy<Value = ([u64; 3], Vec<RandomRwOp>)> {
+    use proptest::prelude::*;
+
+    (
+        any::<[u64; 3]>(),
+        proptest::collection::vec((0..4usize, 0..3usize, any::<bool>(), any::<u64>()), 1..32),
+    )
+}
+
+/// Builds a valid rw trace, sorted by key, from a sequence of random operations. Every
+/// read returns the value of the previous access to its key (or the initial value), and
+/// all stack slots are written before they are accessed.
+fn random_rw_trace(initial_values: [u64; 3], ops: &[RandomRwOp]) -> Vec<Rw> {
+    let address = address!("0x00000000000000000000000000000000000000ef");
+    let account_field_tags = [
+        AccountFieldTag::Nonce,
+        AccountFieldTag::Balance,
+        AccountFieldTag::CodeHash,
+    ];
+
+    let mut current: HashMap<(usize, usize), U256> = HashMap::new();
+    let mut rows = vec![];
+    let mut rw_counter = 0;
+    for i in 0..3 {
+        rw_counter += 1;
+        current.insert((1, i), U256::from(i + 1));
+        rows.push(Rw::Stack {
+            rw_counter,
+            is_write: true,
+            call_id: 1,
+            stack_pointer: 1021 + i,
+            value: U256::from(i + 1),
+        });
+    }
+    for &(tag, i, is_write, written) in ops {
+        rw_counter += 1;
+        let initial_value = match tag {
+            2 | 3 => U256::from(initial_values[i]),
+            _ => U256::zero(),
+        };
+        let value_prev = *current.get(&(tag, i)).unwrap_or(&initial_value);
+        let value = if is_write {
+            U256::from(written)
+        } else {
+            value_prev
+        };
+        current.insert((tag, i), value);
+        rows.push(match tag {
+            0 => Rw::Memory {
+                rw_counter,
+                is_write,
+                call_id: 1,
+                memory_address: 32 * i as u64,
+                value,
+                value_prev,
+            },
+            1 => Rw::Stack {
+                rw_counter,
+                is_write,
+                call_id: 1,
+                stack_pointer: 1021 + i,
+                value,
+            },
+            2 => Rw::Account {
+                rw_counter,
+                is_write,
+                account_address: address,
+                field_tag: account_field_tags[i],
+                value,
+                value_prev,
+            },
+            _ => Rw::AccountStorage {
+                rw_counter,
+                is_write,
+                account_address: address,
+                storage_key: U256::from(i),
+                value,
+                value_prev,
+                tx_id: 1,
+                committed_value: initial_value,
+            },
+        });
+    }
+
+    rows.sort_by_key(Rw::as_key);
+    rows
+}
+
+#[test]
+fn random_rw_traces_are_accepted() {
+    for seed in RW_TRACE_SEEDS {
+        rw_trace_runner(seed)
+            .run(&rw_trace_strategy(), |(initial_values, ops)| {
+                let rows = random_rw_trace(initial_values, &ops);
+                proptest::prop_assert_eq!(verify(rows), Ok(()));
+                Ok(())
+            })
+            .unwrap_or_else(|e| panic!("seed {seed:#x}: {e}"));
+    }
+}
+
+#[test]
+fn random_rw_traces_with_mutations_are_rejected() {
+    use proptest::{prelude::*, sample::Index};
+
+    for seed in RW_TRACE_SEEDS {
+        rw_trace_runner(seed)
+            .run(
+                &(rw_trace_strategy(), any::<Index>(), any::<bool>()),
+                |((initial_values, ops), index, tamper_read)| {
+                    let mut rows = random_rw_trace(initial_values, &ops);
+                    if tamper_read {
+                        // A read that returns a different value than the one last accessed.
+                        let reads: Vec<_> =
+                            (0..rows.len()).filter(|&i| !rows[i].is_write()).collect();
+                        prop_assume!(!reads.is_empty());
+                        match &mut rows[reads[index.index(reads.len())]] {
+                            Rw::Memory { value, .. }
+                            | Rw::Stack { value, .. }
+                            | Rw::Account { value, .. }
+                            | Rw::AccountStorage { value, .. } => *value += U256::one(),
+                            _ => unreachable!(),
+                        }
+                    } else {
+                        // The first access to a stack slot is always a write of a non-zero value,
+                        // turning it into a read is invalid.
+                        match rows.iter_mut().find(|rw| matches!(rw, Rw::Stack { .. })) {
+                            Some(Rw::Stack { is_write, .. }) => *is_write = false,
+                            _ => unreachable!(),
+                        }
+                    }
+                    prop_assert!(verify(rows).is_err());
+                    Ok(())
+                },
+            )
+            .unwrap_or_else(|e| panic!("seed {seed:#x}: {e}"));
+    }
+}
+
 fn prover(rows: Vec<Rw>, overrides: HashMap<(AdviceColumn, isize), Fr>) -> MockProver<'static, Fr> {
     let updates = MptUpdates::mock_from(&rows);
     let circuit = StateCircuit::<Fr> {
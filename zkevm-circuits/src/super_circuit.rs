@@ -84,7 +84,7 @@ use crate::{
         MptTable, PoseidonTable, PowOfRandTable, RlpFsmRlpTable as RlpTable, RwTable, SHA256Table,
         SigTable, TxTable, U16Table, U8Table,
     },
-    tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
+    tx_circuit::{RangeTableRows, TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{circuit_stats, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
     witness::{block_convert, Block, Transaction},
 };
@@ -265,6 +265,10 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 u8_table,
                 u16_table,
                 challenges: challenges_expr.clone(),
+                enable_block_table_lookups: true,
+                max_tx_gas: None,
+                check_l1_msgs_first: false,
+                calldata_gas_costs: Default::default(),
             },
         );
         log_circuit_info(meta, "tx circuit");
@@ -648,6 +652,12 @@ impl<
         challenges: &crate::util::Challenges<Value<Fr>>,
         layouter: &mut impl Layouter<Fr>,
     ) -> Result<(), Error> {
+        // the range tables shared by the sub circuits
+        let range_table_rows = RangeTableRows {
+            u8_rows: config.u8_table.load(layouter)?,
+            u16_rows: config.u16_table.load(layouter)?,
+        };
+
         log::debug!("assigning evm_circuit");
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
@@ -674,8 +684,12 @@ impl<
         self.bytecode_circuit
             .synthesize_sub(&config.bytecode_circuit, challenges, layouter)?;
         log::debug!("assigning tx_circuit");
-        self.tx_circuit
-            .synthesize_sub(&config.tx_circuit, challenges, layouter)?;
+        self.tx_circuit.synthesize_with_challenges(
+            &config.tx_circuit,
+            challenges,
+            Some(range_table_rows),
+            layouter,
+        )?;
         log::debug!("assigning sig_circuit");
         self.sig_circuit
             .synthesize_sub(&config.sig_circuit, challenges, layouter)?;
@@ -767,9 +781,6 @@ impl<
     ) -> Result<(), Error> {
         let challenges = challenges.values(&layouter);
 
-        config.u8_table.load(&mut layouter)?;
-        config.u16_table.load(&mut layouter)?;
-
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
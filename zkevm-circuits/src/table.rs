@@ -8,10 +8,11 @@ use crate::{
     },
     exp_circuit::param::{OFFSET_INCREMENT, ROWS_PER_STEP},
     impl_expr,
+    tx_circuit::TxHasher,
     util::{build_tx_log_address, Challenges},
     witness::{
-        Block, BlockContexts, Bytecode, MptUpdateRow, MptUpdates, RlpFsmWitnessGen, Rw, RwMap,
-        RwRow, Transaction,
+        Block, BlockContexts, Bytecode, MptUpdateRow, MptUpdates, RlpFsmWitnessGen, RlpTable, Rw,
+        RwMap, RwRow, Transaction,
     },
 };
 use bus_mapping::{
@@ -244,6 +245,30 @@ impl TxTable {
         max_calldata: usize,
         chain_id: u64,
         challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.load_with_hasher(
+            layouter,
+            txs,
+            max_txs,
+            max_calldata,
+            chain_id,
+            &TxHasher::default(),
+            challenges,
+        )
+    }
+
+    /// Same as `load`, but `TxSignHash` and `TxHash` are computed by `hasher`, like
+    /// the Tx Circuit does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_with_hasher<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        txs: &[Transaction],
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        hasher: &TxHasher,
+        challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedCell<F, F>>, Error> {
         assert!(
             txs.len() <= max_txs,
@@ -333,7 +358,7 @@ impl TxTable {
                     .collect::<Vec<Transaction>>();
                 for (i, tx) in txs.iter().chain(padding_txs.iter()).enumerate() {
                     debug_assert_eq!(i + 1, tx.id);
-                    let tx_data = tx.table_assignments_fixed(*challenges);
+                    let tx_data = tx.table_assignments_fixed(hasher, *challenges);
                     for row in tx_data {
                         tx_value_cells.push(assign_row(
                             &mut region,
@@ -571,6 +596,24 @@ pub enum CallContextFieldTag {
 }
 impl_expr!(CallContextFieldTag);
 
+impl CallContextFieldTag {
+    /// Returns true if the field is only written once, when the call context is
+    /// created, and is only read afterwards.
+    pub fn is_read_only(self) -> bool {
+        matches!(
+            self,
+            CallContextFieldTag::CallerId
+                | CallContextFieldTag::TxId
+                | CallContextFieldTag::Depth
+                | CallContextFieldTag::CallerAddress
+                | CallContextFieldTag::CalleeAddress
+                | CallContextFieldTag::IsStatic
+                | CallContextFieldTag::IsRoot
+                | CallContextFieldTag::IsCreate
+        )
+    }
+}
+
 /// The RwTable shared between EVM Circuit and State Circuit, which contains
 /// traces of the EVM state operations.
 #[derive(Clone, Copy, Debug)]
@@ -1448,20 +1491,27 @@ impl KeccakTable {
     pub fn assignments<F: Field>(
         input: &[u8],
         challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let mut keccak = Keccak::default();
+        keccak.update(input);
+        Self::assignments_with_output(input, &keccak.digest(), challenges)
+    }
+
+    /// Generate the table assignments from a byte array input and its hash, so that
+    /// the table can be used for hash functions other than keccak256.
+    /// Used only for dev_load
+    pub fn assignments_with_output<F: Field>(
+        input: &[u8],
+        output: &[u8],
+        challenges: &Challenges<Value<F>>,
     ) -> Vec<[Value<F>; 4]> {
         let input_rlc = challenges
             .keccak_input()
             .map(|challenge| rlc::value(input.iter().rev(), challenge));
         let input_len = F::from(input.len() as u64);
-        let mut keccak = Keccak::default();
-        keccak.update(input);
-        let output = keccak.digest();
-        let output_rlc = challenges.evm_word().map(|challenge| {
-            rlc::value(
-                &Word::from_big_endian(output.as_slice()).to_le_bytes(),
-                challenge,
-            )
-        });
+        let output_rlc = challenges
+            .evm_word()
+            .map(|challenge| rlc::value(&Word::from_big_endian(output).to_le_bytes(), challenge));
 
         vec![[
             Value::known(F::one()),
@@ -1501,6 +1551,31 @@ impl KeccakTable {
         layouter: &mut impl Layouter<F>,
         inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
         challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let inputs_with_outputs = inputs
+            .into_iter()
+            .map(|input| {
+                let mut keccak = Keccak::default();
+                keccak.update(input);
+                (input.as_slice(), keccak.digest())
+            })
+            .collect::<Vec<_>>();
+        self.dev_load_with_outputs(
+            layouter,
+            inputs_with_outputs
+                .iter()
+                .map(|(input, output)| (*input, output.as_slice())),
+            challenges,
+        )
+    }
+
+    /// Same as `dev_load`, but each input comes with its hash, so that the table can
+    /// hold hashes other than keccak256 ones
+    pub fn dev_load_with_outputs<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs_with_outputs: impl IntoIterator<Item = (&'a [u8], &'a [u8])> + Clone,
+        challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "keccak table",
@@ -1523,8 +1598,8 @@ impl KeccakTable {
                 offset += 1;
 
                 let keccak_table_columns = <KeccakTable as LookupTable<F>>::advice_columns(self);
-                for input in inputs.clone() {
-                    for row in Self::assignments(input, challenges) {
+                for (input, output) in inputs_with_outputs.clone() {
+                    for row in Self::assignments_with_output(input, output, challenges) {
                         region.assign_fixed(
                             || format!("keccak table row {offset}"),
                             self.q_enable,
@@ -2376,6 +2451,15 @@ impl RlpFsmRlpTable {
             .map(|row| row.rlp_table)
             .collect::<Vec<_>>();
 
+        self.dev_load_rows(layouter, &rows)
+    }
+
+    /// Load the given output rows into the RLP table (only for dev).
+    pub fn dev_load_rows<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        rows: &[RlpTable<F>],
+    ) -> Result<(), Error> {
         let assign_any = |region: &mut Region<'_, F>,
                           annotation: &'static str,
                           col: Column<Any>,
@@ -3126,11 +3210,14 @@ impl<const MAX: usize> RangeTable<MAX> {
         Self(inner)
     }
 
-    /// Assign values to the table.
-    pub fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    /// Assign values to the table. Returns the number of assigned rows, for the circuits
+    /// sharing the table to check with `assert_full_range` that it is loaded.
+    pub fn load<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<usize, Error> {
+        let mut rows = 0;
         layouter.assign_table(
             || format!("range table [0, {MAX})"),
             |mut table| {
+                rows = 0;
                 for i in 0..MAX {
                     table.assign_cell(
                         || format!("range at offset = {i}"),
@@ -3138,11 +3225,26 @@ impl<const MAX: usize> RangeTable<MAX> {
                         i,
                         || Value::known(F::from(i as u64)),
                     )?;
+                    rows += 1;
                 }
 
                 Ok(())
             },
-        )
+        )?;
+
+        Ok(rows)
+    }
+
+    /// Check that `rows`, the number of rows assigned by `load`, cover [0, MAX). A shared
+    /// table that no circuit loaded is then caught before the synthesis of the circuits
+    /// looking it up, instead of as their lookup failures.
+    pub fn assert_full_range(&self, rows: usize) -> Result<(), Error> {
+        if rows < MAX {
+            log::error!("range table [0, {MAX}) is used with only {rows} rows loaded");
+            return Err(Error::Synthesis);
+        }
+
+        Ok(())
     }
 }
 
@@ -11,9 +11,15 @@ mod dev;
 mod test;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 pub use dev::TxCircuitTester as TestTxCircuit;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::{TxAssignmentColumn, TxAssignmentExport, TxAssignmentLog, TxTableAssignment};
 
 use crate::{
     evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    keccak_circuit::{
+        keccak_packed_multi::get_num_rows_per_round,
+        param::{NUM_ROUNDS, RATE},
+    },
     // sig_circuit::SigCircuit,
     table::{
         BlockContextFieldTag::{CumNumTxs, NumAllTxs, NumTxs},
@@ -28,7 +34,7 @@ use crate::{
     },
     util::{
         is_zero::{IsZeroChip, IsZeroConfig},
-        keccak, rlc_be_bytes, SubCircuit, SubCircuitConfig,
+        keccak, log2_ceil, rlc_be_bytes, SubCircuit, SubCircuitConfig,
     },
     witness,
     witness::{
@@ -37,7 +43,7 @@ use crate::{
             L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashPreEip155, TxSignEip155,
             TxSignEip1559, TxSignEip2930, TxSignPreEip155,
         },
-        RlpTag,
+        RecoveryCache, RlpTable as RlpTableRow, RlpTag,
         RlpTag::{GasCost, Len, Null, RLC},
         Tag::TxType as RLPTxType,
         Transaction,
@@ -45,12 +51,13 @@ use crate::{
 };
 use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
 use eth_types::{
+    evm_types::gas_utils::tx_access_list_gas_cost,
     geth_types::{
         access_list_size, TxType,
         TxType::{Eip155, Eip1559, Eip2930, L1Msg, PreEip155},
     },
     sign_types::SignData,
-    AccessList, Address, Field, ToAddress, ToBigEndian, ToScalar,
+    AccessList, Address, Field, ToAddress, ToBigEndian, ToScalar, Word, H256,
 };
 use ethers_core::utils::keccak256;
 use gadgets::{
@@ -58,20 +65,24 @@ use gadgets::{
     comparator::{ComparatorChip, ComparatorConfig, ComparatorInstruction},
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
     less_than::{LtChip, LtConfig, LtInstruction},
-    util::{and, not, select, sum, Expr},
+    util::{and, expr_from_u16, not, select, sum, Expr},
 };
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
     poly::Rotation,
 };
-use log::error;
+use log::{error, warn};
 use num::Zero;
+use serde::Serialize;
 use std::{
     cell::RefCell,
+    cmp::{max, min},
     collections::{BTreeMap, BTreeSet, HashMap},
     iter,
     marker::PhantomData,
+    ops::Range,
+    sync::Arc,
 };
 
 use crate::{util::Challenges, witness::rlp_fsm::get_rlp_len_tag_length};
@@ -88,8 +99,16 @@ pub const TX_LEN: usize = 28;
 pub const TX_HASH_OFFSET: usize = 21;
 /// Offset of ChainID tag in the tx table
 pub const CHAIN_ID_OFFSET: usize = 12;
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Offset of TxSignHash tag in the tx table
+pub const TX_SIGN_HASH_OFFSET: usize = 18;
+/// Offset of TxType tag in the tx table
+pub const TX_TYPE_OFFSET: usize = 22;
+/// Number of u16 limbs of a 20-byte address
+const ADDRESS_U16_LIMBS: usize = 10;
+/// Name of the region holding the tx table and the auxiliary columns of the tx circuit
+const TX_REGION_NAME: &str = "tx table aux";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum LookupCondition {
     // lookup into tx table
     TxCalldata,
@@ -103,6 +122,225 @@ enum LookupCondition {
     TxAccessList,
 }
 
+/// Constraints on the columns that hold a value of the tx a row belongs to
+trait TxConstraintBuilder<F: Field>: ConstrainBuilderCommon<F> {
+    /// Requires each of `fields` to have the same value at the current and the next
+    /// row, i.e. to be copied across the rows of the same tx.
+    fn require_copied_across_tx(
+        &mut self,
+        meta: &mut VirtualCells<F>,
+        fields: &[(&'static str, Column<Advice>)],
+    ) {
+        for &(name, column) in fields {
+            self.require_equal(
+                name,
+                meta.query_advice(column, Rotation::next()),
+                meta.query_advice(column, Rotation::cur()),
+            );
+        }
+    }
+}
+
+impl<F: Field> TxConstraintBuilder<F> for BaseConstraintBuilder<F> {}
+
+/// Cells of (num_l1_msgs, num_l2_txs) of each block, keyed by block number
+pub type BlockNumTxsCells<F> = BTreeMap<u64, [AssignedCell<F, F>; 2]>;
+
+/// The tx-derived witness that the PI circuit consumes, see [`TxCircuit::pi_inputs`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxPiInputs {
+    /// (num_l1_msgs, num_l2_txs) of each block, keyed by block number
+    pub num_txs_per_block: BTreeMap<u64, (u64, u64)>,
+    /// Hash of each tx, i.e. the raw bytes behind the `TxHash` row of the tx table
+    pub tx_hashes: Vec<[u8; 32]>,
+    /// Whether each tx is an L1 msg
+    pub l1_msg_flags: Vec<bool>,
+}
+
+/// Gas costs of the call data bytes of a tx.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalldataGasCosts {
+    /// Gas cost of a zero byte
+    pub zero_byte: u64,
+    /// Gas cost of a non-zero byte
+    pub non_zero_byte: u64,
+}
+
+impl Default for CalldataGasCosts {
+    /// The costs since Istanbul (EIP-2028)
+    fn default() -> Self {
+        Self {
+            zero_byte: 4,
+            non_zero_byte: 16,
+        }
+    }
+}
+
+impl CalldataGasCosts {
+    /// Returns the gas cost of `byte`.
+    pub fn byte_cost(&self, byte: u8) -> u64 {
+        if byte == 0 {
+            self.zero_byte
+        } else {
+            self.non_zero_byte
+        }
+    }
+
+    /// Returns the gas cost of the call data `data`.
+    pub fn data_cost(&self, data: &[u8]) -> u64 {
+        data.iter().map(|byte| self.byte_cost(*byte)).sum()
+    }
+}
+
+/// Numbers of rows loaded into the range tables looked up by the tx circuit, as
+/// returned by their `load`. The tables are usually shared with other circuits and
+/// loaded by only one of them, so a forgotten load is caught with
+/// `RangeTable::assert_full_range` before the assignment of the tx circuit instead of as
+/// lookup failures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeTableRows {
+    /// Rows loaded into the u8 table
+    pub u8_rows: usize,
+    /// Rows loaded into the u16 table
+    pub u16_rows: usize,
+}
+
+/// The gates and lookups configured by the tx circuit, see [`TxCircuitConfig::describe`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CircuitDescription {
+    /// Gates in the order they are configured
+    pub gates: Vec<GateDescription>,
+    /// Lookups in the order they are configured
+    pub lookups: Vec<LookupDescription>,
+}
+
+/// A gate configured by the tx circuit.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GateDescription {
+    /// Name of the gate
+    pub name: String,
+    /// Names of the constraints of the gate
+    pub constraints: Vec<String>,
+    /// Columns queried by the constraints, including their activation conditions
+    pub columns: Vec<String>,
+}
+
+/// A lookup configured by the tx circuit.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LookupDescription {
+    /// Name of the lookup
+    pub name: String,
+    /// Activation condition of the lookup, if it is in the lookup report
+    pub condition: Option<String>,
+    /// Columns queried by the inputs
+    pub columns: Vec<String>,
+    /// Columns of the looked up table
+    pub table_columns: Vec<String>,
+}
+
+impl CircuitDescription {
+    // describes the gates and lookups of `meta` after the first `num_gates` gates and
+    // `num_lookups` lookups, i.e. the ones configured since then
+    fn new<F: Field>(
+        meta: &ConstraintSystem<F>,
+        num_gates: usize,
+        num_lookups: usize,
+        lookup_report: &[(&'static str, &'static str)],
+    ) -> Self {
+        let gates = meta.gates()[num_gates..]
+            .iter()
+            .map(|gate| GateDescription {
+                name: gate.name().to_string(),
+                constraints: (0..gate.polynomials().len())
+                    .map(|i| gate.constraint_name(i).to_string())
+                    .collect(),
+                columns: queried_columns(gate.polynomials()),
+            })
+            .collect();
+        let lookups = meta.lookups[num_lookups..]
+            .iter()
+            .map(|lookup| LookupDescription {
+                name: lookup.name().to_string(),
+                condition: lookup_report
+                    .iter()
+                    .find(|(name, _)| *name == lookup.name())
+                    .map(|(_, condition)| condition.to_string()),
+                columns: queried_columns(lookup.input_expressions()),
+                table_columns: queried_columns(lookup.table_expressions()),
+            })
+            .collect();
+
+        Self { gates, lookups }
+    }
+}
+
+// Returns the sorted columns queried by `exprs`, e.g. "advice[3]".
+fn queried_columns<F: Field>(exprs: &[Expression<F>]) -> Vec<String> {
+    let union = |mut a: BTreeSet<String>, b: BTreeSet<String>| {
+        a.extend(b);
+        a
+    };
+    exprs
+        .iter()
+        .flat_map(|expr| {
+            expr.evaluate(
+                &|_| BTreeSet::new(),
+                &|selector| BTreeSet::from([format!("{selector:?}")]),
+                &|query| BTreeSet::from([format!("fixed[{}]", query.column_index())]),
+                &|query| BTreeSet::from([format!("advice[{}]", query.column_index())]),
+                &|query| BTreeSet::from([format!("instance[{}]", query.column_index())]),
+                &|_| BTreeSet::new(),
+                &|a| a,
+                &union,
+                &union,
+                &|a, _| a,
+            )
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Hash function of the hash table in which `TxSignHash` and `TxHash` are looked up.
+/// The hash table is passed as `keccak_table` in [`TxCircuitConfigArgs`] and must have
+/// the layout of [`KeccakTable`].
+pub trait TxHashFunction: std::fmt::Debug + Send + Sync {
+    /// Returns the hash of `input`
+    fn hash(&self, input: &[u8]) -> [u8; 32];
+}
+
+/// keccak256, the default hash function of the tx circuit
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak256;
+
+impl TxHashFunction for Keccak256 {
+    fn hash(&self, input: &[u8]) -> [u8; 32] {
+        keccak256(input)
+    }
+}
+
+/// Shared handle to the hash function of a TxCircuit, keccak256 by default
+#[derive(Clone, Debug)]
+pub struct TxHasher(Arc<dyn TxHashFunction>);
+
+impl TxHasher {
+    /// Return a new TxHasher
+    pub fn new(hash_function: impl TxHashFunction + 'static) -> Self {
+        Self(Arc::new(hash_function))
+    }
+
+    /// Returns the hash of `input`
+    pub fn hash(&self, input: &[u8]) -> [u8; 32] {
+        self.0.hash(input)
+    }
+}
+
+impl Default for TxHasher {
+    fn default() -> Self {
+        Self::new(Keccak256)
+    }
+}
+
 #[derive(Clone, Debug)]
 struct RlpTableInputValue<F: Field> {
     tag: RlpTag,
@@ -114,12 +352,22 @@ struct RlpTableInputValue<F: Field> {
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
 pub struct TxCircuitConfig<F: Field> {
+    // number of rows reserved at the end of the circuit, at least meta.minimum_rows()
     minimum_rows: usize,
+    // degree of the constraint system after the tx circuit is configured
+    degree: usize,
+    // (name, activation condition) of the lookups configured by the tx circuit
+    lookup_report: Vec<(&'static str, &'static str)>,
+    // gates and lookups configured by the tx circuit
+    description: CircuitDescription,
 
     // This is only true at the first row of calldata part of tx table
     q_calldata_first: Column<Fixed>,
     q_calldata_last: Column<Fixed>,
-    // A selector which is enabled at 1st row
+    // This is only true at the padding rows after the calldata part of tx table
+    q_padding: Column<Fixed>,
+    // A selector which is enabled at 1st row. Like every fixed column it is committed in
+    // the verifying key, so the prover can't enable it elsewhere and no gate counts it.
     q_first: Column<Fixed>,
     tx_table: TxTable,
     tx_tag_bits: BinaryNumberConfig<TxFieldTag, 5>,
@@ -161,7 +409,12 @@ pub struct TxCircuitConfig<F: Field> {
     block_num: Column<Advice>,
     block_num_unchanged: IsEqualConfig<F>,
     num_all_txs_acc: Column<Advice>,
+    /// num_all_txs_acc = num_l1_msgs + num_l2_txs
+    num_l1_msgs: Column<Advice>,
+    num_l2_txs: Column<Advice>,
     total_l1_popped_before: Column<Advice>,
+    /// Number of txs in the block up to the current tx, equals num_txs at the last tx
+    num_txs_acc: Column<Advice>,
 
     /// Columns for accumulating call_data_length and call_data_gas_cost
     /// A boolean advice column, which is turned on only for the last byte in
@@ -185,6 +438,13 @@ pub struct TxCircuitConfig<F: Field> {
     tx_id_gt_prev_cnt: LtConfig<F, 2>,
     /// Cumulative number of txs up to a block
     cum_num_txs: Column<Advice>,
+    /// Optional maximum gas of a tx, with gas < max_tx_gas + 1 at the Gas row
+    max_tx_gas: Option<(u64, LtConfig<F, 8>)>,
+    /// Gas costs of the call data bytes accumulated in calldata_gas_cost_acc
+    calldata_gas_costs: CalldataGasCosts,
+    /// Little-endian u16 limbs of the CallerAddress and CalleeAddress values, and of the
+    /// tx id difference to the next row in the calldata region (first 2 limbs)
+    address_limbs: [Column<Advice>; ADDRESS_U16_LIMBS],
     /// Number of txs in a block
     num_txs: Column<Advice>,
 
@@ -195,6 +455,7 @@ pub struct TxCircuitConfig<F: Field> {
 
     // External tables
     block_table: BlockTable,
+    enable_block_table_lookups: bool,
     rlp_table: RlpTable,
     keccak_table: KeccakTable,
 
@@ -212,6 +473,13 @@ pub struct TxCircuitConfig<F: Field> {
     // no ommittance in access list dynamic section
     field_rlc: Column<Advice>,
 
+    // tx table rows written by the last call to `assign`, in assignment order
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    assignment_log: RefCell<Vec<TxTableAssignment<F>>>,
+    // values written by the last call to `assign` to the columns of the tx circuit region
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    assignment_export: RefCell<TxAssignmentExport<F>>,
+
     _marker: PhantomData<F>,
 }
 
@@ -233,6 +501,19 @@ pub struct TxCircuitConfigArgs<F: Field> {
     pub u16_table: U16Table,
     /// Challenges
     pub challenges: crate::util::Challenges<Expression<F>>,
+    /// Whether to look up NumTxs, CumNumTxs and NumAllTxs in the block table. It's
+    /// true unless the tx circuit is proven standalone, i.e. only to verify the
+    /// signatures and RLP encodings of the txs without a block table.
+    pub enable_block_table_lookups: bool,
+    /// If set, the gas of each tx must not exceed this maximum, e.g. the block gas
+    /// limit. Not checked by default.
+    pub max_tx_gas: Option<u64>,
+    /// Whether to check that the L1 msgs of each block precede its L2 txs. Not checked
+    /// by default.
+    pub check_l1_msgs_first: bool,
+    /// Gas costs of the zero and non-zero call data bytes, 4 and 16 by default. The
+    /// CallDataGasCost of each tx must be computed with the same costs.
+    pub calldata_gas_costs: CalldataGasCosts,
 }
 
 impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
@@ -250,10 +531,22 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             u8_table,
             u16_table,
             challenges,
+            enable_block_table_lookups,
+            max_tx_gas,
+            check_l1_msgs_first,
+            calldata_gas_costs,
         }: Self::ConfigArgs,
     ) -> Self {
         let q_enable = tx_table.q_enable;
-
+        // the gates and lookups configured before the tx circuit's
+        let (num_gates, num_lookups) = (meta.gates().len(), meta.lookups.len());
+
+        // Columns get their indices in the order they are allocated below, including the
+        // ones allocated by the chips. To keep the indices of the existing columns stable,
+        // and so the verifying keys of circuits built on top of them, new columns are
+        // allocated after all of them, i.e. after the last chip configured before the
+        // gates. Columns of optional features come last, as they are only allocated when
+        // enabled. `tx_circuit_column_order` pins the indices.
         let q_first = meta.fixed_column();
         let q_calldata_first = meta.fixed_column();
         let q_calldata_last = meta.fixed_column();
@@ -397,7 +690,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             |meta| meta.query_advice(tx_table.tx_id, Rotation::next()),
         );
 
-        // testing if value is zero for tags
+        // testing if value is zero for tags. value_is_zero.expr() is only sound at rows
+        // with one of these tags, where the inverse witness is constrained; at any other
+        // row the prover can make it 1 by assigning a zero inverse. Every gate using it
+        // must be conditioned on one of these tags (at the queried rotation).
         let value_is_zero = IsZeroChip::configure(
             meta,
             |meta| {
@@ -410,6 +706,9 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                         is_data_length(meta),
                         // if call data byte is zero, then gas_cost = 4 (16 otherwise)
                         is_data(meta),
+                        // if there is no access list address, then skip lookup to the access
+                        // list dynamic section
+                        is_access_list_addresses_len(meta),
                     ]),
                 ])
             },
@@ -417,16 +716,85 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             |meta| meta.advice_column_in(SecondPhase), // value is at 2nd phase
         );
 
+        // block num is the last row of each tx's fixed rows and since block num is
+        // copied to TX_LEN rows. The row at which tag = BlockNum and tx_id = i,
+        // its next row has tx_id = i+1. That is, we can use Rotation::next() to get next
+        // tx's all meta-infos (including block_num, tx_nonce, num_all_txs_acc, ...)
+        let block_num_unchanged = IsEqualChip::configure(
+            meta,
+            |meta| {
+                and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_tag_block_num, Rotation::cur()),
+                ])
+            },
+            |meta| meta.query_advice(block_num, Rotation::next()),
+            |meta| meta.query_advice(block_num, Rotation::cur()),
+        );
+
+        // prev block's cum_num_txs < tx_id
+        let tx_id_gt_prev_cnt = LtChip::configure(
+            meta,
+            |meta| {
+                and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_tag_block_num, Rotation::cur()),
+                ])
+            },
+            |meta| {
+                let num_txs = meta.query_advice(num_txs, Rotation::cur());
+                let cum_num_txs = meta.query_advice(cum_num_txs, Rotation::cur());
+
+                cum_num_txs - num_txs
+            },
+            |meta| meta.query_advice(tx_table.tx_id, Rotation::cur()),
+            u8_table.into(),
+        );
+
+        // tx_id <= cum_num_txs
+        let tx_id_cmp_cum_num_txs = ComparatorChip::configure(
+            meta,
+            |meta| {
+                and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_tag_block_num, Rotation::cur()),
+                ])
+            },
+            |meta| meta.query_advice(tx_table.tx_id, Rotation::cur()),
+            |meta| meta.query_advice(cum_num_txs, Rotation::cur()),
+            u8_table.into(),
+        );
+
+        // columns added later, allocated after all of the columns above
+        let q_padding = meta.fixed_column();
+        // num_l1_msgs includes the skipped l1 msgs
+        let num_l1_msgs = meta.advice_column();
+        let num_l2_txs = meta.advice_column();
+        // counts the txs of each block in circuit instead of trusting the block table
+        let num_txs_acc = meta.advice_column();
+        // u16 limbs of CallerAddress and CalleeAddress
+        let address_limbs = [(); ADDRESS_U16_LIMBS].map(|_| meta.advice_column());
+
         // tx_id transition in the fixed part of tx table
         meta.create_gate("tx_id starts with 1", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
             // the first row in tx table are all-zero rows
+            cb.require_zero(
+                "tx_id == 0 in the leading row",
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+            );
             cb.require_equal(
                 "tx_id == 1",
                 meta.query_advice(tx_table.tx_id, Rotation::next()),
                 1.expr(),
             );
+            // the leading row is followed by the 1st tx even if all txs are padding txs,
+            // as there is at least one (padding) tx in the circuit.
+            cb.require_zero(
+                "tx_id changes after the leading row",
+                tx_id_unchanged.is_equal_expression.clone(),
+            );
 
             cb.gate(meta.query_fixed(q_first, Rotation::cur()))
         });
@@ -452,29 +820,30 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                         meta.query_advice(tx_table.tx_id, Rotation::cur()),
                     );
                     // tx meta infos that extracted at some row and need to be copied to all rows of
-                    // same tx
-                    let tx_meta_info_fields = vec![
-                        ("tx_type", tx_type),             // extracted at SigV row
-                        ("is_padding_tx", is_padding_tx), // extracted at CallerAddress row
-                        ("sv_address", sv_address),       // extracted at ChainID row
-                        ("block_num", block_num),         // extracted at BlockNum row
-                        ("total_l1_popped_before", total_l1_popped_before),
-                        ("num_txs", num_txs),
-                        ("cum_num_txs", cum_num_txs),
-                        ("num_all_txs_acc", num_all_txs_acc),
-                        // is_l1_msg does not need to spread out as it's extracted from tx_type
-
-                        // these do not need to spread out as they are related to tx_table.tag
-                        // (which is fixed col) is_chain_id,
-                        // is_caller_address, is_tag_block_num, is_calldata
-                    ];
-                    for (col_name, meta_info) in tx_meta_info_fields {
-                        cb.require_equal(
-                            col_name,
-                            meta.query_advice(meta_info, Rotation::next()),
-                            meta.query_advice(meta_info, Rotation::cur()),
-                        );
-                    }
+                    // same tx. As the equality is between adjacent rows, a value extracted at a
+                    // later row (e.g. block_num at the last fixed row) also binds the earlier rows
+                    // down to the Nonce row.
+                    cb.require_copied_across_tx(
+                        meta,
+                        &[
+                            ("tx_type", tx_type),             // extracted at TxType row
+                            ("is_padding_tx", is_padding_tx), // extracted at CallerAddress row
+                            ("sv_address", sv_address),       // extracted at ChainID row
+                            ("block_num", block_num),         // extracted at BlockNum row
+                            ("total_l1_popped_before", total_l1_popped_before),
+                            ("num_txs", num_txs),
+                            ("cum_num_txs", cum_num_txs),
+                            ("num_all_txs_acc", num_all_txs_acc),
+                            ("num_l1_msgs", num_l1_msgs),
+                            ("num_l2_txs", num_l2_txs),
+                            ("num_txs_acc", num_txs_acc),
+                            // is_l1_msg does not need to spread out as it's extracted from tx_type
+
+                            // these do not need to spread out as they are related to tx_table.tag
+                            // (which is fixed col) is_chain_id,
+                            // is_caller_address, is_tag_block_num, is_calldata
+                        ],
+                    );
                 },
             );
 
@@ -559,6 +928,36 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 );
             });
 
+            // tags which are not looked up in the RLP table carry no RLP value
+            let is_rlp_tag_null = sum::expr(
+                rlp_tag_map
+                    .iter()
+                    .filter(|(_, tag)| *tag == Null)
+                    .map(|(expr, _)| expr.clone()),
+            );
+            cb.condition(is_rlp_tag_null, |cb| {
+                cb.require_zero(
+                    "rlp_tag == Null => tx_value_rlc == 0",
+                    meta.query_advice(tx_value_rlc, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "rlp_tag == Null => tx_value_length == 0",
+                    meta.query_advice(tx_value_length, Rotation::cur()),
+                );
+                // the condition gates of the RLP lookups only cover the tx types they
+                // apply to, so rule out the Null tags for every tx type here
+                for condition in [
+                    LookupCondition::RlpSignTag,
+                    LookupCondition::RlpHashTag,
+                    LookupCondition::L1MsgHash,
+                ] {
+                    cb.require_zero(
+                        "rlp_tag == Null => no lookup into RLP table",
+                        meta.query_advice(lookup_conditions[&condition], Rotation::cur()),
+                    );
+                }
+            });
+
             cb.require_equal(
                 "associated rlp_tag",
                 meta.query_advice(rlp_tag, Rotation::cur()),
@@ -567,6 +966,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 }),
             );
 
+            // as is_none is boolean, this makes IsCreate == 1 exactly when the
+            // CalleeAddress is not present
             cb.condition(is_to(meta), |cb| {
                 cb.require_equal(
                     "is_create == is_none",
@@ -576,6 +977,13 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 );
             });
 
+            cb.condition(is_create(meta), |cb| {
+                cb.require_boolean(
+                    "IsCreate is boolean",
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                );
+            });
+
             let is_none_expr = meta.query_advice(is_none, Rotation::cur());
             // is_none == true
             cb.condition(is_none_expr.expr(), |cb| {
@@ -585,6 +993,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     meta.query_advice(tx_table.value, Rotation::cur()),
                     0.expr(),
                 );
+                // and the value is encoded with 0 bytes in RLP, which also
+                // implies that a non-zero length is never none.
+                cb.require_zero(
+                    "is_none is true => tx_value_length == 0",
+                    meta.query_advice(tx_value_length, Rotation::cur()),
+                );
             });
 
             // CallData is none =>
@@ -622,6 +1036,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         //////////////////////////////////////////////////////////
         ///// Constraints for booleans that reducing degree  /////
         //////////////////////////////////////////////////////////
+        // tx_table.tag is a fixed column and the fixed rows of a tx never use the CallData
+        // tag, so this forces is_calldata == 0 in the whole fixed region. Together with the
+        // calldata region gates below, no row can switch the fixed region gates off by
+        // claiming to be a CallData row.
         meta.create_gate("is_calldata", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -682,6 +1100,124 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             },
         );
 
+        // The calldata region (i.e. the dynamic section holding calldata and access list
+        // rows) starts right at the q_calldata_first row (max_txs * TX_LEN + 1) and lasts
+        // until the q_calldata_last row.
+        meta.create_gate("calldata region starts after the fixed region", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_dynamic = |meta: &mut VirtualCells<F>, rotation: Rotation| {
+                sum::expr([
+                    meta.query_advice(is_calldata, rotation),
+                    meta.query_advice(is_access_list, rotation),
+                ])
+            };
+
+            cb.require_equal(
+                "q_calldata_first row is in the calldata region",
+                is_dynamic(meta, Rotation::cur()),
+                1.expr(),
+            );
+            cb.require_zero(
+                "row before q_calldata_first is in the fixed region",
+                is_dynamic(meta, Rotation::prev()),
+            );
+
+            // q_enable is needed as there is no calldata region when max_calldata = 0
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_fixed(q_calldata_first, Rotation::cur()),
+            ]))
+        });
+
+        // The "tx call data init" gate only applies when the first row of the calldata
+        // region belongs to a tx. Together with the two gates around it (the row before
+        // q_calldata_first is in the fixed region and the calldata region is contiguous),
+        // this makes q_calldata_first the only init row of the calldata region.
+        meta.create_gate(
+            "q_calldata_first is the init of the calldata region",
+            |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                cb.require_zero(
+                    "index == 0 at q_calldata_first",
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                );
+                // tx_id = 0 at the first row means that there is no calldata or access list at
+                // all, so the next row must be padding as well.
+                cb.condition(
+                    and::expr([
+                        tx_id_is_zero.expr(Rotation::cur())(meta),
+                        not::expr(meta.query_fixed(q_calldata_last, Rotation::cur())),
+                    ]),
+                    |cb| {
+                        cb.require_zero(
+                            "tx_id' == 0 if tx_id == 0 at q_calldata_first",
+                            meta.query_advice(tx_table.tx_id, Rotation::next()),
+                        );
+                    },
+                );
+
+                cb.gate(and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_fixed(q_calldata_first, Rotation::cur()),
+                ]))
+            },
+        );
+
+        // The padding rows after the calldata region are not enabled, hold no tx
+        // table entry and enable no lookup, so that none of the gates querying the
+        // next row of the calldata region can be satisfied by a non-Null padding row.
+        meta.create_gate("padding region", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "tag == Null in padding region",
+                meta.query_fixed(tx_table.tag, Rotation::cur()),
+                (TxFieldTag::Null as u64).expr(),
+            );
+            cb.require_zero(
+                "q_enable == 0 in padding region",
+                meta.query_fixed(q_enable, Rotation::cur()),
+            );
+            for (name, col) in [
+                ("tx_id == 0 in padding region", tx_table.tx_id),
+                ("index == 0 in padding region", tx_table.index),
+                ("value == 0 in padding region", tx_table.value),
+            ] {
+                cb.require_zero(name, meta.query_advice(col, Rotation::cur()));
+            }
+            for col in lookup_conditions.values() {
+                cb.require_zero(
+                    "lookup condition == 0 in padding region",
+                    meta.query_advice(*col, Rotation::cur()),
+                );
+            }
+
+            cb.gate(meta.query_fixed(q_padding, Rotation::cur()))
+        });
+
+        meta.create_gate("calldata region is contiguous", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_dynamic = |meta: &mut VirtualCells<F>, rotation: Rotation| {
+                sum::expr([
+                    meta.query_advice(is_calldata, rotation),
+                    meta.query_advice(is_access_list, rotation),
+                ])
+            };
+
+            cb.require_zero(
+                "calldata region is never followed by the fixed region",
+                is_dynamic(meta, Rotation::cur()) * not::expr(is_dynamic(meta, Rotation::next())),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_fixed(q_calldata_last, Rotation::cur())),
+            ]))
+        });
+
         meta.create_gate("is_caller_address", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -920,8 +1456,45 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        // The Keccak lookup reads its input_len from tx_table.value, which is the same
+        // cell the RLP table lookup reads as the output of the Len tag. Require that every
+        // row doing a Keccak lookup is also a Len row looked up in the RLP table, so that
+        // the hashed length is always the RLP-encoded length.
+        meta.create_gate("keccak input_len is the RLP Len output", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "rlp_tag == Len",
+                meta.query_advice(rlp_tag, Rotation::cur()),
+                usize::from(Len).expr(),
+            );
+            cb.require_equal(
+                "Len row is looked up in RLP table",
+                sum::expr([
+                    meta.query_advice(
+                        lookup_conditions[&LookupCondition::RlpSignTag],
+                        Rotation::cur(),
+                    ),
+                    meta.query_advice(
+                        lookup_conditions[&LookupCondition::RlpHashTag],
+                        Rotation::cur(),
+                    ),
+                    meta.query_advice(
+                        lookup_conditions[&LookupCondition::L1MsgHash],
+                        Rotation::cur(),
+                    ),
+                ]),
+                1.expr(),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(lookup_conditions[&LookupCondition::Keccak], Rotation::cur()),
+            ]))
+        });
+
         // lookups to RLP table, Tx table, Keccak table
-        Self::configure_lookups(
+        let mut lookup_report = Self::configure_lookups(
             meta,
             q_enable,
             rlp_tag,
@@ -969,6 +1542,23 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
+        // the L1MsgHash format has no chain id
+        meta.create_gate("chain_id == 0 for L1 msg", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.condition(meta.query_advice(is_chain_id, Rotation::cur()), |cb| {
+                cb.require_zero(
+                    "chain_id == 0",
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                );
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_l1_msg, Rotation::cur()),
+            ]))
+        });
+
         ///////////////////////////////////////////////////////////////////////
         ///////////////  constraints on num_all_txs  // ///////////////////////
         ///////////////////////////////////////////////////////////////////////
@@ -1000,25 +1590,12 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
-        // block num is the last row of each tx's fixed rows and since block num is
-        // copied to TX_LEN rows. The row at which tag = BlockNum and tx_id = i,
-        // its next row has tx_id = i+1. That is, we can use Rotation::next() to get next
-        // tx's all meta-infos (including block_num, tx_nonce, num_all_txs_acc, ...)
-        let block_num_unchanged = IsEqualChip::configure(
-            meta,
-            |meta| {
-                and::expr([
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    meta.query_advice(is_tag_block_num, Rotation::cur()),
-                ])
-            },
-            |meta| meta.query_advice(block_num, Rotation::next()),
-            |meta| meta.query_advice(block_num, Rotation::cur()),
-        );
-
         meta.lookup("block_num is non-decreasing till padding txs", |meta| {
             // Block nums like this [1, 3, 5, 4, 0] is rejected by this. But [1, 2, 3, 5, 0] is
             // acceptable.
+            // Whatever block_num the trailing padding txs have, it is never read: the lookup
+            // is disabled at the last real tx and between padding txs, as is_padding_tx is
+            // fixed by the caller address of each tx.
             let lookup_condition = and::expr([
                 // next row should not belong to a padding tx
                 not::expr(meta.query_advice(is_padding_tx, Rotation::next())),
@@ -1036,8 +1613,24 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         meta.create_gate("num_all_txs in a block", |meta| {
             let mut cb = BaseConstraintBuilder::default();
             let queue_index = tx_nonce;
+            cb.require_equal(
+                "num_all_txs_acc = num_l1_msgs + num_l2_txs",
+                meta.query_advice(num_all_txs_acc, Rotation::cur()),
+                meta.query_advice(num_l1_msgs, Rotation::cur())
+                    + meta.query_advice(num_l2_txs, Rotation::cur()),
+            );
             // first tx in tx table
             cb.condition(meta.query_fixed(q_first, Rotation::next()), |cb| {
+                cb.require_equal(
+                    "num_l2_txs = is_l1_msg ? 0 : 1",
+                    meta.query_advice(num_l2_txs, Rotation::cur()),
+                    not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
+                );
+                cb.require_equal(
+                    "num_txs_acc = 1",
+                    meta.query_advice(num_txs_acc, Rotation::cur()),
+                    1.expr(),
+                );
                 cb.require_equal(
                     "num_all_txs_acc = is_l1_msg ? queue_index - total_l1_popped_before + 1 : 1",
                     meta.query_advice(num_all_txs_acc, Rotation::cur()),
@@ -1084,6 +1677,17 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                             1.expr(),
                         ),
                     );
+                    cb.require_equal(
+                        "num_l2_txs' - num_l2_txs = is_l1_msg' ? 0 : 1",
+                        meta.query_advice(num_l2_txs, Rotation::next())
+                            - meta.query_advice(num_l2_txs, Rotation::cur()),
+                        not::expr(meta.query_advice(is_l1_msg, Rotation::next())),
+                    );
+                    cb.require_equal(
+                        "num_txs_acc' = num_txs_acc + 1",
+                        meta.query_advice(num_txs_acc, Rotation::next()),
+                        meta.query_advice(num_txs_acc, Rotation::cur()) + 1.expr(),
+                    );
                 },
             );
 
@@ -1127,6 +1731,52 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                             1.expr(),
                         ),
                     );
+                    cb.require_equal(
+                        "init new block's num_l2_txs",
+                        meta.query_advice(num_l2_txs, Rotation::next()),
+                        not::expr(meta.query_advice(is_l1_msg, Rotation::next())),
+                    );
+                    cb.require_equal(
+                        "init new block's num_txs_acc",
+                        meta.query_advice(num_txs_acc, Rotation::next()),
+                        1.expr(),
+                    );
+                },
+            );
+
+            // last non-padding tx in cur block (including the last tx of the fixed part)
+            cb.condition(
+                and::expr([
+                    not::expr(block_num_unchanged.expr()),
+                    not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "num_txs_acc == num_txs at the last tx of a block",
+                        meta.query_advice(num_txs_acc, Rotation::cur()),
+                        meta.query_advice(num_txs, Rotation::cur()),
+                    );
+                },
+            );
+
+            // next tx is a padding tx. Padding txs are all l2 txs, so num_all_txs_acc starts
+            // at 1 for the first padding tx and increments by 1 for each of the following ones,
+            // independently of the block_num assigned to them.
+            cb.condition(
+                and::expr([
+                    not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                    meta.query_advice(is_padding_tx, Rotation::next()),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "num_all_txs_acc of padding txs increments by 1",
+                        meta.query_advice(num_all_txs_acc, Rotation::next()),
+                        select::expr(
+                            meta.query_advice(is_padding_tx, Rotation::cur()),
+                            meta.query_advice(num_all_txs_acc, Rotation::cur()) + 1.expr(),
+                            1.expr(),
+                        ),
+                    );
                 },
             );
 
@@ -1141,25 +1791,27 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
-        meta.lookup_any("num_all_txs in block table", |meta| {
-            let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
-            let block_num = meta.query_advice(tx_table.value, Rotation::cur());
-            let num_all_txs_acc = meta.query_advice(num_all_txs_acc, Rotation::cur());
-
-            let input_expr = vec![NumAllTxs.expr(), block_num, num_all_txs_acc];
-            let table_expr = block_table.table_exprs(meta);
-            let condition = and::expr([
-                is_tag_block_num,
-                not::expr(block_num_unchanged.expr()), // the last tx in each block
-                not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
-            ]);
+        if enable_block_table_lookups {
+            meta.lookup_any("num_all_txs in block table", |meta| {
+                let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
+                let block_num = meta.query_advice(tx_table.value, Rotation::cur());
+                let num_all_txs_acc = meta.query_advice(num_all_txs_acc, Rotation::cur());
+
+                let input_expr = vec![NumAllTxs.expr(), block_num, num_all_txs_acc];
+                let table_expr = block_table.table_exprs(meta);
+                let condition = and::expr([
+                    is_tag_block_num,
+                    not::expr(block_num_unchanged.expr()), // the last tx in each block
+                    not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+                ]);
 
-            input_expr
-                .into_iter()
-                .zip(table_expr)
-                .map(|(input, table)| (input * condition.clone(), table))
-                .collect::<Vec<_>>()
-        });
+                input_expr
+                    .into_iter()
+                    .zip(table_expr)
+                    .map(|(input, table)| (input * condition.clone(), table))
+                    .collect::<Vec<_>>()
+            });
+        }
 
         ///////////////////////////////////////////////////////////////////////
         ///////  constraints on block_table's num_txs & num_cum_txs  //////////
@@ -1179,25 +1831,6 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
-        // prev block's cum_num_txs < tx_id
-        let tx_id_gt_prev_cnt = LtChip::configure(
-            meta,
-            |meta| {
-                and::expr([
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    meta.query_advice(is_tag_block_num, Rotation::cur()),
-                ])
-            },
-            |meta| {
-                let num_txs = meta.query_advice(num_txs, Rotation::cur());
-                let cum_num_txs = meta.query_advice(cum_num_txs, Rotation::cur());
-
-                cum_num_txs - num_txs
-            },
-            |meta| meta.query_advice(tx_table.tx_id, Rotation::cur()),
-            u8_table.into(),
-        );
-
         // last non-padding tx must have tx_id == cum_num_txs
         meta.create_gate(
             "last non-padding tx must have tx_id == cum_num_txs",
@@ -1222,20 +1855,6 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             },
         );
 
-        // tx_id <= cum_num_txs
-        let tx_id_cmp_cum_num_txs = ComparatorChip::configure(
-            meta,
-            |meta| {
-                and::expr([
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    meta.query_advice(is_tag_block_num, Rotation::cur()),
-                ])
-            },
-            |meta| meta.query_advice(tx_table.tx_id, Rotation::cur()),
-            |meta| meta.query_advice(cum_num_txs, Rotation::cur()),
-            u8_table.into(),
-        );
-
         meta.create_gate("tx_id <= cum_num_txs", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -1250,61 +1869,167 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
-        meta.lookup_any("num_txs in block table", |meta| {
-            let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
-            let block_num = meta.query_advice(tx_table.value, Rotation::cur());
-            let num_txs = meta.query_advice(num_txs, Rotation::cur());
+        if enable_block_table_lookups {
+            meta.lookup_any("num_txs in block table", |meta| {
+                let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
+                let block_num = meta.query_advice(tx_table.value, Rotation::cur());
+                let num_txs = meta.query_advice(num_txs, Rotation::cur());
 
-            let input_expr = vec![NumTxs.expr(), block_num, num_txs];
-            let table_expr = block_table.table_exprs(meta);
-            let condition = and::expr([
-                is_tag_block_num,
-                not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
-                meta.query_fixed(q_enable, Rotation::cur()),
-            ]);
+                let input_expr = vec![NumTxs.expr(), block_num, num_txs];
+                let table_expr = block_table.table_exprs(meta);
+                let condition = and::expr([
+                    is_tag_block_num,
+                    not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                ]);
 
-            input_expr
-                .into_iter()
-                .zip(table_expr)
-                .map(|(input, table)| (input * condition.clone(), table))
-                .collect::<Vec<_>>()
+                input_expr
+                    .into_iter()
+                    .zip(table_expr)
+                    .map(|(input, table)| (input * condition.clone(), table))
+                    .collect::<Vec<_>>()
+            });
+
+            meta.lookup_any("cum_num_txs in block table", |meta| {
+                let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
+                let block_num = meta.query_advice(tx_table.value, Rotation::cur());
+                let cum_num_txs = meta.query_advice(cum_num_txs, Rotation::cur());
+
+                let input_expr = vec![CumNumTxs.expr(), block_num, cum_num_txs];
+                let table_expr = block_table.table_exprs(meta);
+                let condition = and::expr([
+                    is_tag_block_num,
+                    not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                ]);
+
+                input_expr
+                    .into_iter()
+                    .zip(table_expr)
+                    .map(|(input, table)| (input * condition.clone(), table))
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        // gas <= max_tx_gas
+        let max_tx_gas = max_tx_gas.map(|max_tx_gas| {
+            assert!(max_tx_gas < u64::MAX, "max_tx_gas + 1 must fit into u64");
+            let gas_lt_max = LtChip::configure(
+                meta,
+                |meta| and::expr([meta.query_fixed(q_enable, Rotation::cur()), is_gas(meta)]),
+                |meta| meta.query_advice(tx_table.value, Rotation::cur()),
+                |_| (max_tx_gas + 1).expr(),
+                u8_table.into(),
+            );
+
+            meta.create_gate("gas <= max_tx_gas", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
+
+                cb.require_equal(
+                    "gas < max_tx_gas + 1",
+                    gas_lt_max.is_lt(meta, None),
+                    1.expr(),
+                );
+
+                cb.gate(and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    is_gas(meta),
+                ]))
+            });
+
+            (max_tx_gas, gas_lt_max)
         });
 
-        meta.lookup_any("cum_num_txs in block table", |meta| {
-            let is_tag_block_num = meta.query_advice(is_tag_block_num, Rotation::cur());
-            let block_num = meta.query_advice(tx_table.value, Rotation::cur());
-            let cum_num_txs = meta.query_advice(cum_num_txs, Rotation::cur());
+        // the next tx of the same block can only be an L1 msg if the current one is
+        if check_l1_msgs_first {
+            meta.create_gate("L1 msgs precede L2 txs in a block", |meta| {
+                let mut cb = BaseConstraintBuilder::default();
 
-            let input_expr = vec![CumNumTxs.expr(), block_num, cum_num_txs];
-            let table_expr = block_table.table_exprs(meta);
-            let condition = and::expr([
-                is_tag_block_num,
-                not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+                cb.require_zero(
+                    "L2 tx is not followed by an L1 msg",
+                    and::expr([
+                        not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
+                        meta.query_advice(is_l1_msg, Rotation::next()),
+                    ]),
+                );
+
+                cb.gate(and::expr([
+                    meta.query_fixed(q_enable, Rotation::cur()),
+                    meta.query_advice(is_tag_block_num, Rotation::cur()),
+                    not::expr(meta.query_advice(is_calldata, Rotation::next())),
+                    block_num_unchanged.expr(),
+                ]))
+            });
+        }
+
+        // CallerAddress and CalleeAddress fit into 20 bytes
+        let is_address = |meta: &mut VirtualCells<F>| {
+            and::expr([
                 meta.query_fixed(q_enable, Rotation::cur()),
-            ]);
+                sum::expr([
+                    meta.query_advice(is_caller_address, Rotation::cur()),
+                    is_to(meta),
+                ]),
+            ])
+        };
+        meta.create_gate("address fits into 20 bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
 
-            input_expr
-                .into_iter()
-                .zip(table_expr)
-                .map(|(input, table)| (input * condition.clone(), table))
-                .collect::<Vec<_>>()
+            let limbs = address_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            cb.require_equal(
+                "address == sum(limb_i * 2^(16 * i))",
+                meta.query_advice(tx_table.value, Rotation::cur()),
+                expr_from_u16(&limbs),
+            );
+
+            cb.gate(is_address(meta))
         });
+        for limb in address_limbs {
+            meta.lookup("address limb fits into u16", |meta| {
+                let limb = meta.query_advice(limb, Rotation::cur());
+                vec![(is_address(meta) * limb, u16_table.into())]
+            });
+        }
+        lookup_report.push((
+            "address limb fits into u16",
+            "CallerAddress or CalleeAddress",
+        ));
 
         ////////////////////////////////////////////////////////////////////////
         ///////////  CallData length and gas_cost calculation  /////////////////
         ////////////////////////////////////////////////////////////////////////
-        meta.lookup("tx_id_diff must in u16", |meta| {
+        // the address limb columns are free in the calldata region, where the first two
+        // hold the tx id difference to the next row so that it can exceed u16
+        let tx_id_diff_limbs = [address_limbs[0], address_limbs[1]];
+        let is_tx_id_diff = |meta: &mut VirtualCells<F>| {
             let q_enable = meta.query_fixed(q_enable, Rotation::next());
             let is_calldata = meta.query_advice(is_calldata, Rotation::cur());
-            let tx_id = meta.query_advice(tx_table.tx_id, Rotation::cur());
-            let tx_id_next = meta.query_advice(tx_table.tx_id, Rotation::next());
             let tx_id_next_is_zero = tx_id_is_zero.expr(Rotation::next())(meta);
 
-            let lookup_condition =
-                and::expr([q_enable, is_calldata, not::expr(tx_id_next_is_zero)]);
+            and::expr([q_enable, is_calldata, not::expr(tx_id_next_is_zero)])
+        };
+        // This also makes tx_id non-decreasing across calldata rows: the limbs can only
+        // sum up to [0, 2^32), which never reaches a wrapped negative difference.
+        meta.create_gate("tx_id_diff fits into u32", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let limbs = tx_id_diff_limbs.map(|limb| meta.query_advice(limb, Rotation::cur()));
+            cb.require_equal(
+                "tx_id::next - tx_id == sum(limb_i * 2^(16 * i))",
+                meta.query_advice(tx_table.tx_id, Rotation::next())
+                    - meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                expr_from_u16(&limbs),
+            );
 
-            vec![(lookup_condition * (tx_id_next - tx_id), u16_table.into())]
+            cb.gate(is_tx_id_diff(meta))
         });
+        for limb in tx_id_diff_limbs {
+            meta.lookup("tx_id_diff limb fits into u16", |meta| {
+                let limb = meta.query_advice(limb, Rotation::cur());
+                vec![(is_tx_id_diff(meta) * limb, u16_table.into())]
+            });
+        }
+        lookup_report.push(("tx_id_diff limb fits into u16", "CallData"));
 
         meta.create_gate("last row of call data", |meta| {
             let q_calldata_last = meta.query_fixed(q_calldata_last, Rotation::cur());
@@ -1331,7 +2056,11 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             let value_is_zero = value_is_zero.expr(Rotation::cur())(meta);
-            let gas_cost = select::expr(value_is_zero, 4.expr(), 16.expr());
+            let gas_cost = select::expr(
+                value_is_zero,
+                calldata_gas_costs.zero_byte.expr(),
+                calldata_gas_costs.non_zero_byte.expr(),
+            );
 
             cb.require_equal(
                 "index == 0",
@@ -1355,6 +2084,35 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
+        // the calldata region must start with the calldata of a real tx which has calldata,
+        // i.e. whose CallDataRLC row is not none
+        meta.lookup_any("calldata init tx in TxTable", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_calldata_first, Rotation::cur()),
+                not::expr(tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+            let is_real_tx_with_calldata = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_advice(is_none, Rotation::cur())),
+                not::expr(meta.query_advice(is_padding_tx, Rotation::cur())),
+            ]);
+
+            vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                CallDataRLC.expr(),
+                1.expr(),
+            ]
+            .into_iter()
+            .zip([
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_fixed(tx_table.tag, Rotation::cur()),
+                is_real_tx_with_calldata,
+            ])
+            .map(|(arg, table)| (enable.clone() * arg, table))
+            .collect()
+        });
+        lookup_report.push(("calldata init tx in TxTable", "q_calldata_first"));
+
         meta.create_gate("tx call data bytes", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -1375,7 +2133,11 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 );
 
                 let value_next_is_zero = value_is_zero.expr(Rotation::next())(meta);
-                let gas_cost_next = select::expr(value_next_is_zero, 4.expr(), 16.expr());
+                let gas_cost_next = select::expr(
+                    value_next_is_zero,
+                    calldata_gas_costs.zero_byte.expr(),
+                    calldata_gas_costs.non_zero_byte.expr(),
+                );
                 // call data gas cost accumulator check.
                 cb.require_equal(
                     "calldata_gas_cost_acc::next == calldata_gas_cost::cur + gas_cost_next",
@@ -1395,6 +2157,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             // calldata section or an access list section for the same tx
 
             // on the final call data byte, if there's no access list, tx_id must change.
+            // As tx_id only increases in the calldata region, each tx has one calldata
+            // section, so this also makes is_final == 1 exactly once per tx with calldata.
             cb.condition(
                 and::expr([
                     is_final_cur.expr(),
@@ -1416,7 +2180,11 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 ]),
                 |cb| {
                     let value_next_is_zero = value_is_zero.expr(Rotation::next())(meta);
-                    let gas_cost_next = select::expr(value_next_is_zero, 4.expr(), 16.expr());
+                    let gas_cost_next = select::expr(
+                        value_next_is_zero,
+                        calldata_gas_costs.zero_byte.expr(),
+                        calldata_gas_costs.non_zero_byte.expr(),
+                    );
 
                     cb.require_equal(
                         "index' == 0",
@@ -1472,6 +2240,23 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             ]))
         });
 
+        // the calldata rows after the calldata of the last tx are zeros which don't carry
+        // any section_rlc, so that it can't accumulate anything there
+        meta.create_gate("tx call data zeros", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_zero(
+                "section_rlc == 0",
+                meta.query_advice(section_rlc, Rotation::cur()),
+            );
+
+            cb.gate(and::expr(vec![
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_calldata, Rotation::cur()),
+                meta.query_advice(is_tx_id_zero, Rotation::cur()),
+            ]))
+        });
+
         ////////////////////////////////////////////////////////////////////////
         ///////////  Access List Constraints (if available on tx)  /////////////
         ////////////////////////////////////////////////////////////////////////
@@ -1662,13 +2447,30 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 },
             );
 
-            // TODO:
-            //  4. eip1559 tx: v Є {0, 1}
-            //  5. eip2930 tx: v Є {0, 1}
+            //  4. eip1559 / eip2930 tx: v Є {0, 1}
+            cb.condition(
+                and::expr([
+                    is_chain_id.expr(),
+                    sum::expr([
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip2930, Rotation::cur()),
+                    ]),
+                ]),
+                |cb| {
+                    let v = meta.query_advice(tx_table.value, Rotation::next());
+                    cb.require_boolean("V Є {0, 1}", v);
+                },
+            );
+
+            // Each case only leaves the parity of v free, which is pinned down by the
+            // recovery id used in the "Sig table lookup".
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        // The caller address of an L1 msg is not recovered from a signature. Instead it is
+        // looked up in the RLP table with the L1MsgHash format, so the tx hash, which is part
+        // of the public input, commits to the sender of the L1 msg.
         meta.create_gate(
             "caller address == sv_address if it's not zero and tx_type != L1Msg",
             |meta| {
@@ -1694,9 +2496,13 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 
         Self {
             minimum_rows: meta.minimum_rows(),
+            degree: meta.degree(),
+            description: CircuitDescription::new(meta, num_gates, num_lookups, &lookup_report),
+            lookup_report,
             q_first,
             q_calldata_first,
             q_calldata_last,
+            q_padding,
             tx_tag_bits: tag_bits,
             tx_type,
             tx_type_bits,
@@ -1721,7 +2527,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             block_num,
             block_num_unchanged,
             num_all_txs_acc,
+            num_l1_msgs,
+            num_l2_txs,
             total_l1_popped_before,
+            num_txs_acc,
             is_l1_msg,
             is_eip2930,
             is_eip1559,
@@ -1733,6 +2542,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             sv_address,
             sig_table,
             block_table,
+            enable_block_table_lookups,
+            max_tx_gas,
+            calldata_gas_costs,
+            address_limbs,
             tx_table,
             keccak_table,
             rlp_table,
@@ -1744,6 +2557,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             is_access_list_address,
             is_access_list_storage_key,
             field_rlc,
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            assignment_log: Default::default(),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            assignment_export: Default::default(),
             _marker: PhantomData,
             num_txs,
         }
@@ -1751,6 +2568,39 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 }
 
 impl<F: Field> TxCircuitConfig<F> {
+    /// Return the number of rows reserved at the end of the circuit.
+    pub fn minimum_rows(&self) -> usize {
+        self.minimum_rows
+    }
+
+    /// Reserve `minimum_rows` rows at the end of the circuit instead of the
+    /// `meta.minimum_rows()` of the constraint system, e.g. for rows used by a layouter
+    /// shared with other circuits. The number of reserved rows can only be increased.
+    pub fn set_minimum_rows(&mut self, minimum_rows: usize) {
+        assert!(
+            minimum_rows >= self.minimum_rows,
+            "minimum_rows can only be increased from {}",
+            self.minimum_rows
+        );
+        self.minimum_rows = minimum_rows;
+    }
+
+    /// Return the degree of the constraint system after the tx circuit has been configured.
+    pub fn max_degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Return the name and activation condition of each lookup configured by the tx circuit.
+    pub fn lookup_report(&self) -> &[(&'static str, &'static str)] {
+        &self.lookup_report
+    }
+
+    /// Return the gates and lookups configured by the tx circuit, with the columns they
+    /// query, e.g. to be serialized for review.
+    pub fn describe(&self) -> CircuitDescription {
+        self.description.clone()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn configure_lookups(
         meta: &mut ConstraintSystem<F>,
@@ -1782,7 +2632,9 @@ impl<F: Field> TxCircuitConfig<F> {
         al_idx: Column<Advice>,
         sk_idx: Column<Advice>,
         sks_acc: Column<Advice>,
-    ) {
+    ) -> Vec<(&'static str, &'static str)> {
+        // (name, activation condition) of each lookup, reported for debugging purposes
+        let mut lookup_report = vec![];
         macro_rules! is_tx_type {
             ($var:ident, $type_variant:ident) => {
                 let $var = |meta: &mut VirtualCells<F>| {
@@ -1797,7 +2649,9 @@ impl<F: Field> TxCircuitConfig<F> {
         /////////////////////////////////////////////////////////////////
         /////////////////    tx table lookups     ///////////////////////
         /////////////////////////////////////////////////////////////////
-        // lookup to check CallDataGasCost of the tx's call data.
+        // lookup to check CallDataGasCost of the tx's call data. Together with
+        // "CallDataGasCost.value == 0" for txs without call data, this ties CallDataGasCost
+        // to the calldata_gas_cost_acc computed from the call data bytes.
         meta.lookup_any("tx call data gas cost in TxTable", |meta| {
             // if call data length != 0, then we can lookup the calldata gas cost on the
             // last row of the tx's call data bytes.
@@ -1825,6 +2679,7 @@ impl<F: Field> TxCircuitConfig<F> {
             .map(|(arg, table)| (enable.clone() * arg, table))
             .collect()
         });
+        lookup_report.push(("tx call data gas cost in TxTable", "TxCalldata condition"));
         // We need to handle the case in which some of the call data bytes is skipped in
         // the tx table. If the call data length is larger than 0, then we will
         // do lookup in the tx table to make sure the last call data byte in tx
@@ -1854,6 +2709,38 @@ impl<F: Field> TxCircuitConfig<F> {
             .map(|(arg, table)| (enable.clone() * arg, table))
             .collect()
         });
+        lookup_report.push((
+            "is_final call data byte should be present",
+            "TxCalldata condition",
+        ));
+        // The other way round, the last byte of each tx's calldata has index
+        // call_data_length - 1, so that a tx with CallDataLength == 0 has no calldata rows.
+        meta.lookup_any("calldata length in TxTable", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_calldata, Rotation::cur()),
+                not::expr(tx_id_is_zero.expr(Rotation::cur())(meta)),
+                meta.query_advice(is_final, Rotation::cur()),
+            ]);
+
+            vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                CallDataLength.expr(),
+                meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(),
+            ]
+            .into_iter()
+            .zip([
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_fixed(tx_table.tag, Rotation::cur()),
+                meta.query_advice(tx_table.value, Rotation::cur()),
+            ])
+            .map(|(arg, table)| (enable.clone() * arg, table))
+            .collect()
+        });
+        lookup_report.push((
+            "calldata length in TxTable",
+            "is_calldata && tx_id != 0 && is_final",
+        ));
         meta.lookup_any("lookup CallDataRLC in the calldata part", |meta| {
             let is_call_data = meta.query_advice(is_calldata, Rotation::cur());
             let section_rlc = meta.query_advice(section_rlc, Rotation::cur());
@@ -1881,6 +2768,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        lookup_report.push((
+            "lookup CallDataRLC in the calldata part",
+            "is_calldata && tx_id != 0 && is_final",
+        ));
         meta.lookup_any("lookup AccessListAddressLen in the TxTable", |meta| {
             let enable = and::expr([
                 meta.query_fixed(q_enable, Rotation::cur()),
@@ -1905,6 +2796,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        lookup_report.push((
+            "lookup AccessListAddressLen in the TxTable",
+            "is_access_list && is_final",
+        ));
         meta.lookup_any("lookup AccessListStorageKeysLen in the TxTable", |meta| {
             let enable = and::expr([
                 meta.query_fixed(q_enable, Rotation::cur()),
@@ -1929,6 +2824,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        lookup_report.push((
+            "lookup AccessListStorageKeysLen in the TxTable",
+            "is_access_list && is_final",
+        ));
         meta.lookup_any("lookup AccessListRLC in the TxTable", |meta| {
             let enable = and::expr([
                 meta.query_fixed(q_enable, Rotation::cur()),
@@ -1953,6 +2852,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        lookup_report.push((
+            "lookup AccessListRLC in the TxTable",
+            "is_access_list && is_final",
+        ));
         meta.lookup_any("is_final access list row should be present", |meta| {
             let enable = and::expr(vec![
                 meta.query_fixed(q_enable, Rotation::cur()),
@@ -1984,6 +2887,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enable.expr(), table))
                 .collect()
         });
+        lookup_report.push((
+            "is_final access list row should be present",
+            "TxAccessList condition",
+        ));
 
         /////////////////////////////////////////////////////////////////
         /////////////////    RLP table lookups     //////////////////////
@@ -1992,12 +2899,23 @@ impl<F: Field> TxCircuitConfig<F> {
         is_tx_type!(is_eip155, Eip155);
         is_tx_type!(is_l1_msg, L1Msg);
 
-        // lookup tx type in RLP table for L1Msg only
+        // lookup the EIP-2718 type prefix byte of typed txs (L1Msg, Eip2930 and Eip1559)
+        // in RLP table
         meta.lookup_any("lookup tx type in RLP table", |meta| {
-            let enable = and::expr([meta.query_fixed(q_enable, Rotation::cur()), is_l1_msg(meta)]);
-            let hash_format = L1MsgHash.expr();
-            let tag_value = 0x7E.expr();
-            let tag_bytes_rlc = 0x7E.expr();
+            let is_l1_msg = is_l1_msg(meta);
+            let is_eip2930 = meta.query_advice(is_eip2930, Rotation::cur());
+            let is_eip1559 = meta.query_advice(is_eip1559, Rotation::cur());
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                sum::expr([is_l1_msg.expr(), is_eip2930.expr(), is_eip1559.expr()]),
+            ]);
+            let hash_format = is_l1_msg.expr() * L1MsgHash.expr()
+                + is_eip2930.expr() * TxHashEip2930.expr()
+                + is_eip1559.expr() * TxHashEip1559.expr();
+            let type_byte =
+                is_l1_msg * 0x7E.expr() + is_eip2930 * 0x01.expr() + is_eip1559 * 0x02.expr();
+            let tag_value = type_byte.expr();
+            let tag_bytes_rlc = type_byte;
             let tag_length = 1.expr();
 
             let input_exprs = vec![
@@ -2021,6 +2939,10 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (enable.expr() * input, table))
                 .collect()
         });
+        lookup_report.push((
+            "lookup tx type in RLP table",
+            "is_l1_msg || is_eip2930 || is_eip1559",
+        ));
 
         // lookup tx tag in RLP table for signing.
         meta.lookup_any("lookup tx tag in RLP Table for signing", |meta| {
@@ -2057,6 +2979,10 @@ impl<F: Field> TxCircuitConfig<F> {
             .map(|(arg, table)| (enable.clone() * arg, table))
             .collect()
         });
+        lookup_report.push((
+            "lookup tx tag in RLP Table for signing",
+            "RlpSignTag condition",
+        ));
 
         // lookup tx tag in RLP table for hashing
         meta.lookup_any("lookup tx tag in RLP Table for hashing", |meta| {
@@ -2099,6 +3025,10 @@ impl<F: Field> TxCircuitConfig<F> {
             .map(|(arg, table)| (enable.clone() * arg, table))
             .collect()
         });
+        lookup_report.push((
+            "lookup tx tag in RLP Table for hashing",
+            "RlpHashTag or L1MsgHash condition",
+        ));
 
         // lookup access list address in RLP table
         // 1. ensure field_rlc is correct
@@ -2136,6 +3066,10 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        lookup_report.push((
+            "Lookup access list address in RLP Table from tx circuit dynamic section (Signing)",
+            "is_access_list_address",
+        ));
         meta.lookup_any(
             "Lookup access list address in RLP Table from tx circuit dynamic section (Hashing)",
             |meta| {
@@ -2169,6 +3103,10 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        lookup_report.push((
+            "Lookup access list address in RLP Table from tx circuit dynamic section (Hashing)",
+            "is_access_list_address",
+        ));
         // lookup access list storage key in RLP table
         // 1. ensure field_rlc is correct
         // 2. ensure value of storage key is correct
@@ -2205,6 +3143,10 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        lookup_report.push((
+            "Lookup access list storage key in RLP Table from tx circuit dynamic section (Signing)",
+            "is_access_list_storage_key",
+        ));
         meta.lookup_any(
             "Lookup access list storage key in RLP Table from tx circuit dynamic section (Hashing)",
             |meta| {
@@ -2238,6 +3180,10 @@ impl<F: Field> TxCircuitConfig<F> {
             },
         );
 
+        lookup_report.push((
+            "Lookup access list storage key in RLP Table from tx circuit dynamic section (Hashing)",
+            "is_access_list_storage_key",
+        ));
         ////////////////////////////////////////////////////////////////////
         /////////////////    Sig table lookups     //////////////////////
         ///////////////// //////////////////////////////////////////////////
@@ -2251,15 +3197,32 @@ impl<F: Field> TxCircuitConfig<F> {
                 meta.query_advice(is_chain_id, Rotation::cur()),
             ]);
 
-            let msg_hash_rlc = meta.query_advice(tx_table.value, Rotation(6));
+            // sig_r and sig_s are not range checked by the tx circuit, this is delegated to
+            // the sig circuit: the sig table holds the RLCs of r and s as canonical scalars
+            // (< n) and its is_valid is 0 if r or s is 0, which fails this lookup as it
+            // requires is_valid == 1. So r and s are in [1, n - 1].
+            // the signed message hash is the value of the TxSignHash row itself, so it can't
+            // diverge from the msg_hash_rlc checked by the sig circuit
+            let msg_hash_rlc = meta.query_advice(
+                tx_table.value,
+                Rotation((TX_SIGN_HASH_OFFSET - CHAIN_ID_OFFSET) as i32),
+            );
             let chain_id = meta.query_advice(tx_table.value, Rotation::cur());
             let sig_v = meta.query_advice(tx_table.value, Rotation(1));
+            // the tx table holds r and s as the evm_word RLC of their big-endian bytes,
+            // which is the same as the sig table's RLC of their little-endian bytes with
+            // increasing powers of evm_word
             let sig_r = meta.query_advice(tx_table.value, Rotation(2));
             let sig_s = meta.query_advice(tx_table.value, Rotation(3));
             let sv_address = meta.query_advice(sv_address, Rotation::cur());
 
+            // the recovery id derived from v according to the tx type, see
+            // `TxType::get_recovery_id`.
             let v = is_eip155(meta) * (sig_v.expr() - 2.expr() * chain_id - 35.expr())
-                + is_pre_eip155(meta) * (sig_v.expr() - 27.expr());
+                + is_pre_eip155(meta) * (sig_v.expr() - 27.expr())
+                + (meta.query_advice(is_eip1559, Rotation::cur())
+                    + meta.query_advice(is_eip2930, Rotation::cur()))
+                    * sig_v.expr();
 
             let input_exprs = vec![
                 1.expr(),     // q_enable = true
@@ -2289,6 +3252,7 @@ impl<F: Field> TxCircuitConfig<F> {
                 .map(|(input, table)| (input * enabled.expr(), table))
                 .collect()
         });
+        lookup_report.push(("Sig table lookup", "!is_l1_msg && is_chain_id"));
 
         ////////////////////////////////////////////////////////////////////
         /////////////////    Keccak table lookups     //////////////////////
@@ -2314,10 +3278,22 @@ impl<F: Field> TxCircuitConfig<F> {
             .map(|(arg, table)| (enable.clone() * arg, table))
             .collect()
         });
+        lookup_report.push((
+            "Keccak table lookup for TxSign and TxHash",
+            "Keccak condition",
+        ));
+
+        for (name, condition) in lookup_report.iter() {
+            log::debug!("tx circuit lookup \"{}\" is enabled on {}", name, condition);
+        }
+
+        lookup_report
     }
 
     /// Assign 1st empty row with tag = Null
     fn assign_null_row(&self, region: &mut Region<'_, F>, offset: &mut usize) -> Result<(), Error> {
+        // the leading row is always followed by the Nonce row of the 1st (real or
+        // padding) tx.
         self.assign_common_part(
             region,
             *offset,
@@ -2329,7 +3305,7 @@ impl<F: Field> TxCircuitConfig<F> {
             Value::known(F::zero()),
         )?;
         let (col_anno, col, col_val) = ("rlp_tag", self.rlp_tag, F::from(usize::from(Null) as u64));
-        region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+        self.assign_advice(region, || col_anno, col, *offset, Value::known(col_val))?;
 
         *offset += 1;
         Ok(())
@@ -2346,263 +3322,18 @@ impl<F: Field> TxCircuitConfig<F> {
         next_tx: Option<&Transaction>,
         total_l1_popped_before: u64,
         num_all_txs_acc: u64,
+        num_l2_txs: u64,
+        num_txs_acc: u64,
         num_txs: u64,
         cum_num_txs: u64,
+        hasher: &TxHasher,
         challenges: &Challenges<Value<F>>,
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(Vec<AssignedCell<F, F>>, [AssignedCell<F, F>; 2]), Error> {
         let keccak_input = challenges.keccak_input();
-        let evm_word = challenges.evm_word();
         let zero_rlc = keccak_input.map(|_| F::zero());
-        let sign_hash = keccak256(tx.rlp_unsigned.as_slice());
-        let hash = keccak256(tx.rlp_signed.as_slice());
-        let sign_hash_rlc = rlc_be_bytes(&sign_hash, evm_word);
-        let hash_rlc = rlc_be_bytes(&hash, evm_word);
         let mut tx_value_cells = vec![];
-        let rlp_sign_tag_length = if tx.tx_type.is_l1_msg() {
-            // l1 msg does not have sign data
-            0
-        } else {
-            get_rlp_len_tag_length(&tx.rlp_unsigned)
-        };
-        let (access_list_address_size, access_list_storage_key_size) =
-            access_list_size(&tx.access_list);
-
-        // fixed_rows of a tx
-        let fixed_rows = vec![
-            // need to be in same order as that tx table load function uses
-            (
-                Nonce, // tx field tag
-                Some(RlpTableInputValue {
-                    tag: Tag::Nonce.into(),
-                    is_none: tx.nonce == 0,
-                    be_bytes_len: tx.nonce.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.nonce.to_be_bytes(), keccak_input),
-                }),
-                Value::known(F::from(tx.nonce)),
-            ),
-            (
-                GasPrice,
-                Some(RlpTableInputValue {
-                    tag: Tag::GasPrice.into(),
-                    is_none: tx.gas_price.is_zero(),
-                    be_bytes_len: tx.gas_price.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.gas_price.to_be_bytes(), keccak_input),
-                }),
-                rlc_be_bytes(&tx.gas_price.to_be_bytes(), evm_word),
-            ),
-            (
-                Gas,
-                Some(RlpTableInputValue {
-                    tag: Tag::Gas.into(),
-                    is_none: tx.gas == 0,
-                    be_bytes_len: tx.gas.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.gas.to_be_bytes(), keccak_input),
-                }),
-                Value::known(F::from(tx.gas)),
-            ),
-            (
-                CallerAddress,
-                Some(RlpTableInputValue {
-                    tag: Tag::Sender.into(),
-                    is_none: false,
-                    be_bytes_len: tx.caller_address.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.caller_address.to_fixed_bytes(), keccak_input),
-                }),
-                Value::known(tx.caller_address.to_scalar().expect("tx.from too big")),
-            ),
-            (
-                CalleeAddress,
-                Some(RlpTableInputValue {
-                    tag: Tag::To.into(),
-                    is_none: tx.callee_address.is_none(),
-                    be_bytes_len: tx.callee_address.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(
-                        tx.callee_address
-                            .map_or(vec![], |callee| callee.to_fixed_bytes().to_vec())
-                            .as_slice(),
-                        keccak_input,
-                    ),
-                }),
-                Value::known(
-                    tx.callee_address
-                        .unwrap_or(Address::zero())
-                        .to_scalar()
-                        .expect("tx.to too big"),
-                ),
-            ),
-            (IsCreate, None, Value::known(F::from(tx.is_create as u64))),
-            (
-                TxFieldTag::Value,
-                Some(RlpTableInputValue {
-                    tag: Tag::Value.into(),
-                    is_none: tx.value.is_zero(),
-                    be_bytes_len: tx.value.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.value.to_be_bytes(), keccak_input),
-                }),
-                rlc_be_bytes(&tx.value.to_be_bytes(), evm_word),
-            ),
-            (
-                CallDataRLC,
-                Some(RlpTableInputValue {
-                    tag: Tag::Data.into(),
-                    is_none: tx.call_data.is_empty(),
-                    be_bytes_len: tx.call_data.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.call_data, keccak_input),
-                }),
-                rlc_be_bytes(&tx.call_data, keccak_input),
-            ),
-            (
-                CallDataLength,
-                None,
-                Value::known(F::from(tx.call_data.len() as u64)),
-            ),
-            (
-                CallDataGasCost,
-                None,
-                Value::known(F::from(tx.call_data_gas_cost)),
-            ),
-            (
-                TxDataGasCost,
-                Some(RlpTableInputValue {
-                    tag: GasCost,
-                    is_none: false,
-                    be_bytes_len: 0,
-                    be_bytes_rlc: zero_rlc,
-                }),
-                Value::known(F::from(tx.tx_data_gas_cost)),
-            ),
-            (
-                ChainID,
-                Some(RlpTableInputValue {
-                    tag: Tag::ChainId.into(),
-                    is_none: tx.chain_id.is_zero(),
-                    be_bytes_len: tx.chain_id.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.chain_id.to_be_bytes(), keccak_input),
-                }),
-                Value::known(F::from(tx.chain_id)),
-            ),
-            (
-                SigV,
-                Some(RlpTableInputValue {
-                    tag: Tag::SigV.into(),
-                    is_none: tx.v.is_zero(),
-                    be_bytes_len: tx.v.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.v.to_be_bytes(), keccak_input),
-                }),
-                Value::known(F::from(tx.v)),
-            ),
-            (
-                SigR,
-                Some(RlpTableInputValue {
-                    tag: Tag::SigR.into(),
-                    is_none: tx.r.is_zero(),
-                    be_bytes_len: tx.r.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.r.to_be_bytes(), keccak_input),
-                }),
-                rlc_be_bytes(&tx.r.to_be_bytes(), evm_word),
-            ),
-            (
-                SigS,
-                Some(RlpTableInputValue {
-                    tag: Tag::SigS.into(),
-                    is_none: tx.s.is_zero(),
-                    be_bytes_len: tx.s.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.s.to_be_bytes(), keccak_input),
-                }),
-                rlc_be_bytes(&tx.s.to_be_bytes(), evm_word),
-            ),
-            (
-                TxSignLength,
-                Some(RlpTableInputValue {
-                    tag: Len,
-                    is_none: false,
-                    be_bytes_len: rlp_sign_tag_length,
-                    be_bytes_rlc: zero_rlc,
-                }),
-                Value::known(F::from(tx.rlp_unsigned.len() as u64)),
-            ),
-            (
-                TxSignRLC,
-                Some(RlpTableInputValue {
-                    tag: RLC,
-                    is_none: false,
-                    be_bytes_len: 0,
-                    be_bytes_rlc: zero_rlc,
-                }),
-                rlc_be_bytes(&tx.rlp_unsigned, keccak_input),
-            ),
-            (TxSignHash, None, sign_hash_rlc),
-            (
-                TxHashLength,
-                Some(RlpTableInputValue {
-                    tag: Len,
-                    is_none: false,
-                    be_bytes_len: get_rlp_len_tag_length(&tx.rlp_signed),
-                    be_bytes_rlc: zero_rlc,
-                }),
-                Value::known(F::from(tx.rlp_signed.len() as u64)),
-            ),
-            (
-                TxHashRLC,
-                Some(RlpTableInputValue {
-                    tag: RLC,
-                    is_none: false,
-                    be_bytes_len: 0,
-                    be_bytes_rlc: zero_rlc,
-                }),
-                rlc_be_bytes(&tx.rlp_signed, keccak_input),
-            ),
-            (TxFieldTag::TxHash, None, hash_rlc),
-            (
-                TxFieldTag::TxType,
-                None,
-                Value::known(F::from(tx.tx_type as u64)),
-            ),
-            (
-                AccessListAddressesLen,
-                None,
-                Value::known(F::from(access_list_address_size)),
-            ),
-            (
-                AccessListStorageKeysLen,
-                None,
-                Value::known(F::from(access_list_storage_key_size)),
-            ),
-            (
-                AccessListRLC,
-                Some(RlpTableInputValue {
-                    tag: RLC,
-                    is_none: false,
-                    be_bytes_len: 0,
-                    be_bytes_rlc: zero_rlc,
-                }),
-                access_list_rlc(&tx.access_list, challenges),
-            ),
-            (
-                MaxFeePerGas,
-                Some(RlpTableInputValue {
-                    tag: Tag::MaxFeePerGas.into(),
-                    is_none: tx.max_fee_per_gas.is_zero(),
-                    be_bytes_len: tx.max_fee_per_gas.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(&tx.max_fee_per_gas.to_be_bytes(), keccak_input),
-                }),
-                rlc_be_bytes(&tx.max_fee_per_gas.to_be_bytes(), evm_word),
-            ),
-            (
-                MaxPriorityFeePerGas,
-                Some(RlpTableInputValue {
-                    tag: Tag::MaxPriorityFeePerGas.into(),
-                    is_none: tx.max_priority_fee_per_gas.is_zero(),
-                    be_bytes_len: tx.max_priority_fee_per_gas.tag_length(),
-                    be_bytes_rlc: rlc_be_bytes(
-                        &tx.max_priority_fee_per_gas.to_be_bytes(),
-                        keccak_input,
-                    ),
-                }),
-                rlc_be_bytes(&tx.max_priority_fee_per_gas.to_be_bytes(), evm_word),
-            ),
-            (BlockNumber, None, Value::known(F::from(tx.block_number))),
-        ];
+        let mut num_txs_cells = None;
+        let fixed_rows = tx_fixed_rows(tx, hasher, challenges);
         for (tx_tag, rlp_input, tx_value) in fixed_rows {
             let rlp_tag = rlp_input.clone().map_or(Null, |input| input.tag);
             let rlp_is_none = rlp_input.clone().map_or(false, |input| input.is_none);
@@ -2611,6 +3342,18 @@ impl<F: Field> TxCircuitConfig<F> {
                 .clone()
                 .map_or(zero_rlc, |input| input.be_bytes_rlc);
             let is_l1_msg = tx.tx_type.is_l1_msg();
+            // the RLP lookup of integer tags relies on be_bytes_rlc and value being the same
+            // number, as they are computed independently by `tx_fixed_rows`
+            if cfg!(debug_assertions)
+                && [Nonce, Gas, CallerAddress, CalleeAddress, ChainID, SigV].contains(&tx_tag)
+            {
+                tx_value
+                    .zip(rlp_be_bytes_rlc)
+                    .zip(keccak_input)
+                    .assert_if_known(|((value, be_bytes_rlc), rand)| {
+                        be_bytes_rlc_matches_value(*value, *be_bytes_rlc, *rand)
+                    });
+            }
             // it's the tx_id of next row
             let tx_id_next = if tx_tag == BlockNumber {
                 next_tx.map_or(0, |tx| tx.id)
@@ -2657,6 +3400,7 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::from(num_all_txs_acc),
                 ),
                 ("num_txs", self.num_txs, F::from(num_txs)),
+                ("num_txs_acc", self.num_txs_acc, F::from(num_txs_acc)),
                 ("cum_num_txs", self.cum_num_txs, F::from(cum_num_txs)),
                 // tx meta info
                 (
@@ -2691,18 +3435,38 @@ impl<F: Field> TxCircuitConfig<F> {
                     F::from((tx_tag == CallerAddress) as u64),
                 ),
             ] {
-                region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+                self.assign_advice(region, || col_anno, col, *offset, Value::known(col_val))?;
+            }
+            let mut cells = vec![];
+            for (col_anno, col, col_val) in [
+                (
+                    "num_l1_msgs",
+                    self.num_l1_msgs,
+                    F::from(num_all_txs_acc - num_l2_txs),
+                ),
+                ("num_l2_txs", self.num_l2_txs, F::from(num_l2_txs)),
+            ] {
+                cells.push(self.assign_advice(
+                    region,
+                    || col_anno,
+                    col,
+                    *offset,
+                    Value::known(col_val),
+                )?);
+            }
+            if tx_tag == BlockNumber {
+                num_txs_cells = Some([cells[0].clone(), cells[1].clone()]);
             }
 
             // 2nd phase columns
             {
                 let (col_anno, col, col_val) =
                     ("tx_value_rlc", self.tx_value_rlc, rlp_be_bytes_rlc);
-                region.assign_advice(|| col_anno, col, *offset, || col_val)?;
+                self.assign_advice(region, || col_anno, col, *offset, col_val)?;
             }
 
             // lookup conditions
-            let mut conditions = HashMap::<LookupCondition, F>::new();
+            let mut conditions = BTreeMap::<LookupCondition, F>::new();
             // 1. lookup to Tx table for CallDataLength and CallDataGasCost
             conditions.insert(LookupCondition::TxCalldata, {
                 let is_data_length = tx_tag == CallDataLength;
@@ -2725,62 +3489,20 @@ impl<F: Field> TxCircuitConfig<F> {
                 }
             });
             // 3. lookup to RLP table for signing (non L1 msg)
-            conditions.insert(LookupCondition::RlpSignTag, {
-                let sign_set = [
-                    Nonce,
-                    Gas,
-                    CalleeAddress,
-                    TxFieldTag::Value,
-                    CallDataRLC,
-                    TxSignLength,
-                    TxSignRLC,
-                ];
-                let is_tag_in_set = sign_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
-                let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = !tx.tx_type.is_pre_eip155() && !is_l1_msg && (tx_tag == ChainID);
-                let case3 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case4 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3 || case4) as u64)
-            });
+            conditions.insert(
+                LookupCondition::RlpSignTag,
+                F::from(is_rlp_sign_lookup(tx, tx_tag) as u64),
+            );
             // 4. lookup to RLP table for hashing (non L1 msg)
-            conditions.insert(LookupCondition::RlpHashTag, {
-                let hash_set = [
-                    Nonce,
-                    Gas,
-                    CalleeAddress,
-                    TxFieldTag::Value,
-                    CallDataRLC,
-                    TxDataGasCost,
-                    SigV,
-                    SigR,
-                    SigS,
-                    TxHashLength,
-                    TxHashRLC,
-                ];
-                let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
-                let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
-                let case3 = tx.tx_type.is_eip1559()
-                    && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
-                F::from((case1 || case2 || case3) as u64)
-            });
+            conditions.insert(
+                LookupCondition::RlpHashTag,
+                F::from(is_rlp_hash_lookup(tx, tx_tag) as u64),
+            );
             // 5. lookup to RLP table for hashing (L1 msg)
-            conditions.insert(LookupCondition::L1MsgHash, {
-                let hash_set = [
-                    Nonce,
-                    Gas,
-                    CalleeAddress,
-                    TxFieldTag::Value,
-                    CallDataRLC,
-                    CallerAddress,
-                    TxHashLength,
-                    TxHashRLC,
-                ];
-
-                let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
-                F::from((is_l1_msg && is_tag_in_set) as u64)
-            });
+            conditions.insert(
+                LookupCondition::L1MsgHash,
+                F::from(is_l1_msg_hash_lookup(tx, tx_tag) as u64),
+            );
             // 6. lookup to Keccak table for tx_sign_hash and tx_hash
             conditions.insert(LookupCondition::Keccak, {
                 let case1 = (tx_tag == TxSignLength) && !is_l1_msg;
@@ -2788,13 +3510,17 @@ impl<F: Field> TxCircuitConfig<F> {
                 F::from((case1 || case2) as u64)
             });
 
+            #[cfg(debug_assertions)]
+            assert_lookup_conditions(tx, tx_tag, &conditions);
+
             // lookup conditions are 1st phase cols
             for (condition, value) in conditions {
-                region.assign_advice(
+                self.assign_advice(
+                    region,
                     || format!("lookup condition {condition:?}"),
                     self.lookup_conditions[&condition],
                     *offset,
-                    || Value::known(value),
+                    Value::known(value),
                 )?;
             }
 
@@ -2821,10 +3547,44 @@ impl<F: Field> TxCircuitConfig<F> {
                 F::from(cum_num_txs - num_txs),
                 F::from(tx.id as u64),
             )?;
+            if [CallerAddress, CalleeAddress].contains(&tx_tag) {
+                let address = if tx_tag == CallerAddress {
+                    tx.caller_address
+                } else {
+                    tx.callee_address.unwrap_or_default()
+                };
+                let address_bytes = address.to_fixed_bytes();
+                // the 20 big-endian bytes as little-endian u16 limbs
+                let limbs = address_bytes
+                    .rchunks(2)
+                    .map(|limb| F::from(u16::from_be_bytes([limb[0], limb[1]]) as u64));
+                for (i, (limb, value)) in self.address_limbs.iter().zip(limbs).enumerate() {
+                    self.assign_advice(
+                        region,
+                        || format!("address limb {i}"),
+                        *limb,
+                        *offset,
+                        Value::known(value),
+                    )?;
+                }
+            }
+            if let Some((max_tx_gas, gas_lt_max)) = self.max_tx_gas {
+                if tx_tag == Gas {
+                    LtChip::construct(gas_lt_max).assign(
+                        region,
+                        *offset,
+                        F::from(tx.gas),
+                        F::from(max_tx_gas + 1),
+                    )?;
+                }
+            }
 
             *offset += 1;
         }
-        Ok(tx_value_cells)
+        Ok((
+            tx_value_cells,
+            num_txs_cells.expect("BlockNumber is one of the fixed rows"),
+        ))
     }
 
     /// Assign calldata byte rows of each tx
@@ -2841,7 +3601,7 @@ impl<F: Field> TxCircuitConfig<F> {
         let mut rlc = challenges.keccak_input().map(|_| F::zero());
         for (idx, byte) in tx.call_data.iter().enumerate() {
             let is_final = idx == (tx.call_data.len() - 1);
-            gas_cost_acc += if *byte == 0 { 4 } else { 16 };
+            gas_cost_acc += self.calldata_gas_costs.byte_cost(*byte);
             rlc = rlc
                 .zip(challenges.keccak_input())
                 .map(|(rlc, keccak_input)| rlc * keccak_input + F::from(*byte as u64));
@@ -2876,11 +3636,11 @@ impl<F: Field> TxCircuitConfig<F> {
                 ("byte", self.calldata_byte, F::from(*byte as u64)),
                 ("is_calldata", self.is_calldata, F::one()),
             ] {
-                region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+                self.assign_advice(region, || col_anno, col, *offset, Value::known(col_val))?;
             }
 
             // 2nd phase columns
-            region.assign_advice(|| "rlc", self.section_rlc, *offset, || rlc)?;
+            self.assign_advice(region, || "rlc", self.section_rlc, *offset, rlc)?;
 
             *offset += 1;
         }
@@ -2888,6 +3648,53 @@ impl<F: Field> TxCircuitConfig<F> {
         Ok(())
     }
 
+    /// Assign the u16 limbs of the tx id difference to the next row at each calldata row
+    /// of `txs`, whose dynamic rows start at `offset`
+    fn assign_tx_id_diff_limbs(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        txs: &[Transaction],
+    ) -> Result<(), Error> {
+        // (tx id, is_calldata) of each row, as laid out by `assign_calldata_rows` and
+        // `assign_access_list_rows`
+        let rows = txs
+            .iter()
+            .flat_map(|tx| {
+                let num_access_list_rows = tx.access_list.as_ref().map_or(0, |access_list| {
+                    access_list
+                        .0
+                        .iter()
+                        .map(|item| 1 + item.storage_keys.len())
+                        .sum()
+                });
+                std::iter::repeat((tx.id, true))
+                    .take(tx.call_data.len())
+                    .chain(std::iter::repeat((tx.id, false)).take(num_access_list_rows))
+            })
+            .collect::<Vec<_>>();
+        for (i, &(tx_id, is_calldata)) in rows.iter().enumerate() {
+            let tx_id_next = rows.get(i + 1).map_or(0, |&(tx_id, _)| tx_id);
+            if !is_calldata || tx_id_next == 0 {
+                continue;
+            }
+            // a decreasing tx id has no valid limbs, leave them 0 for the gate to fail
+            let tx_id_diff = u32::try_from(tx_id_next.saturating_sub(tx_id)).unwrap_or(0);
+            let limbs = [tx_id_diff & 0xffff, tx_id_diff >> 16];
+            for (j, (limb, value)) in self.address_limbs.iter().zip(limbs).enumerate() {
+                self.assign_advice(
+                    region,
+                    || format!("tx_id_diff limb {j}"),
+                    *limb,
+                    offset + i,
+                    Value::known(F::from(value as u64)),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Assign access list rows of each tx
     fn assign_access_list_rows(
         &self,
@@ -2963,13 +3770,13 @@ impl<F: Field> TxCircuitConfig<F> {
                         F::one(),
                     ),
                 ] {
-                    region.assign_advice(|| col_anno, col, *offset, || Value::known(col_val))?;
+                    self.assign_advice(region, || col_anno, col, *offset, Value::known(col_val))?;
                 }
 
-                region.assign_advice(|| "field_rlc", self.field_rlc, *offset, || field_rlc)?;
+                self.assign_advice(region, || "field_rlc", self.field_rlc, *offset, field_rlc)?;
 
                 // 2nd phase columns
-                region.assign_advice(|| "rlc", self.section_rlc, *offset, || section_rlc)?;
+                self.assign_advice(region, || "rlc", self.section_rlc, *offset, section_rlc)?;
 
                 *offset += 1;
 
@@ -3021,19 +3828,20 @@ impl<F: Field> TxCircuitConfig<F> {
                             F::one(),
                         ),
                     ] {
-                        region.assign_advice(
+                        self.assign_advice(
+                            region,
                             || col_anno,
                             col,
                             *offset,
-                            || Value::known(col_val),
+                            Value::known(col_val),
                         )?;
                     }
 
                     // field_rlc to work with section_rlc
-                    region.assign_advice(|| "field_rlc", self.field_rlc, *offset, || field_rlc)?;
+                    self.assign_advice(region, || "field_rlc", self.field_rlc, *offset, field_rlc)?;
 
                     // 2nd phase columns
-                    region.assign_advice(|| "rlc", self.section_rlc, *offset, || section_rlc)?;
+                    self.assign_advice(region, || "rlc", self.section_rlc, *offset, section_rlc)?;
 
                     *offset += 1;
                 }
@@ -3043,6 +3851,54 @@ impl<F: Field> TxCircuitConfig<F> {
         Ok(())
     }
 
+    // Assigns `value` to the advice `column` at `offset` of the tx circuit region and
+    // records it in the assignment export
+    fn assign_advice<A, AR>(
+        &self,
+        region: &mut Region<'_, F>,
+        annotation: A,
+        column: Column<Advice>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error>
+    where
+        A: Fn() -> AR,
+        AR: Into<String>,
+    {
+        #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+        self.export_assignment(TxAssignmentColumn::Advice(column.index()), offset, value);
+        region.assign_advice(annotation, column, offset, || value)
+    }
+
+    // Assigns `value` to the fixed `column` at `offset` of the tx circuit region and
+    // records it in the assignment export
+    fn assign_fixed<A, AR>(
+        &self,
+        region: &mut Region<'_, F>,
+        annotation: A,
+        column: Column<Fixed>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error>
+    where
+        A: Fn() -> AR,
+        AR: Into<String>,
+    {
+        #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+        self.export_assignment(TxAssignmentColumn::Fixed(column.index()), offset, value);
+        region.assign_fixed(annotation, column, offset, || value)
+    }
+
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    fn export_assignment(&self, column: TxAssignmentColumn, offset: usize, value: Value<F>) {
+        let mut known_value = None;
+        value.map(|value| known_value = Some(value));
+        self.assignment_export
+            .borrow_mut()
+            .cells
+            .insert((offset, column), known_value);
+    }
+
     // Assigns to common columns in different parts of tx circuit
     // 1. 1st all zero row
     // 2. fixed rows of each tx
@@ -3091,7 +3947,7 @@ impl<F: Field> TxCircuitConfig<F> {
             ("q_enable", self.tx_table.q_enable, F::one()),
             ("tag", self.tx_table.tag, F::from(usize::from(tag) as u64)),
         ] {
-            region.assign_fixed(|| col_anno, col, offset, || Value::known(col_val))?;
+            self.assign_fixed(region, || col_anno, col, offset, Value::known(col_val))?;
         }
 
         // 1st phase columns
@@ -3121,19 +3977,33 @@ impl<F: Field> TxCircuitConfig<F> {
                 F::from((tx_id == 0) as u64),
             ),
         ] {
-            region.assign_advice(|| col_anno, col, offset, || Value::known(col_val))?;
+            self.assign_advice(region, || col_anno, col, offset, Value::known(col_val))?;
         }
 
-        region.assign_advice(
+        self.assign_advice(
+            region,
             || "access_list_address value",
             self.tx_table.access_list_address,
             offset,
-            || access_list_address,
+            access_list_address,
         )?;
 
         // 2nd phase columns
         let tx_value_cell =
-            region.assign_advice(|| "tx_value", self.tx_table.value, offset, || value)?;
+            self.assign_advice(region, || "tx_value", self.tx_table.value, offset, value)?;
+
+        #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+        {
+            let mut known_value = None;
+            value.map(|value| known_value = Some(value));
+            self.assignment_log.borrow_mut().push(TxTableAssignment {
+                offset,
+                tag,
+                tx_id,
+                index,
+                value: known_value,
+            });
+        }
 
         Ok(tx_value_cell)
     }
@@ -3152,19 +4022,27 @@ impl<F: Field> TxCircuitConfig<F> {
         let tag_chip = BinaryNumberChip::construct(self.tx_tag_bits);
 
         for offset in start..end {
-            region.assign_fixed(
+            self.assign_fixed(
+                region,
                 || "q_enable",
                 self.tx_table.q_enable,
                 offset,
-                || Value::known(F::one()),
+                Value::known(F::one()),
             )?;
-            region.assign_advice(
+            self.assign_advice(
+                region,
                 || "rlp_tag",
                 self.rlp_tag,
                 offset,
-                || Value::known(F::from(usize::from(Null) as u64)),
+                Value::known(F::from(usize::from(Null) as u64)),
+            )?;
+            self.assign_fixed(
+                region,
+                || "tag",
+                self.tx_table.tag,
+                offset,
+                Value::known(tag),
             )?;
-            region.assign_fixed(|| "tag", self.tx_table.tag, offset, || Value::known(tag))?;
             tag_chip.assign(region, offset, &CallData)?;
             // no need to assign tx_id_is_zero_chip for real prover as tx_id = 0
             tx_id_is_zero_chip.assign(region, offset, Value::known(F::zero()))?;
@@ -3184,16 +4062,18 @@ impl<F: Field> TxCircuitConfig<F> {
                 (self.is_final, F::one()),
                 (self.is_calldata, F::one()),
                 (self.calldata_gas_cost_acc, F::zero()),
+                (self.section_rlc, F::zero()),
                 (self.is_tx_id_zero, F::one()),
             ] {
-                region.assign_advice(|| "", col, offset, || Value::known(value))?;
+                self.assign_advice(region, || "", col, offset, Value::known(value))?;
             }
             for col in self.lookup_conditions.values() {
-                region.assign_advice(
+                self.assign_advice(
+                    region,
                     || "lookup condition",
                     *col,
                     offset,
-                    || Value::known(F::zero()),
+                    Value::known(F::zero()),
                 )?;
             }
         }
@@ -3208,12 +4088,30 @@ impl<F: Field> TxCircuitConfig<F> {
         end: usize,
     ) -> Result<(), Error> {
         for offset in start..end {
-            region.assign_fixed(
+            self.assign_fixed(
+                region,
                 || "tag",
                 self.tx_table.tag,
                 offset,
-                || Value::known(F::from(TxFieldTag::Null as u64)),
+                Value::known(F::from(TxFieldTag::Null as u64)),
+            )?;
+            self.assign_fixed(
+                region,
+                || "q_padding",
+                self.q_padding,
+                offset,
+                Value::known(F::one()),
             )?;
+            for col in [
+                self.tx_table.tx_id,
+                self.tx_table.index,
+                self.tx_table.value,
+            ]
+            .into_iter()
+            .chain(self.lookup_conditions.values().copied())
+            {
+                self.assign_advice(region, || "padding", col, offset, Value::known(F::zero()))?;
+            }
         }
 
         Ok(())
@@ -3236,10 +4134,24 @@ pub struct TxCircuit<F: Field> {
     pub chain_id: u64,
     /// Start L1 Queue Index
     pub start_l1_queue_index: u64,
+    /// Hash function behind TxSignHash and TxHash
+    pub hasher: TxHasher,
+    /// Public keys already recovered from tx signatures, shared with other TxCircuits
+    pub recovery_cache: Option<RecoveryCache>,
     /// Size
     pub size: usize,
     /// Tx value cells (exported for PI circuit)
     pub value_cells: RefCell<Option<Vec<AssignedCell<F, F>>>>,
+    /// num_l1_msgs and num_l2_txs cells of each block (exported for PI circuit)
+    pub num_txs_cells: RefCell<Option<BlockNumTxsCells<F>>>,
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    overrides: HashMap<(dev::AdviceColumn, usize), F>,
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    fixed_overrides: HashMap<(dev::FixedColumn, usize), F>,
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    assignment_log: RefCell<Option<TxAssignmentLog<F>>>,
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    assignment_export: RefCell<Option<TxAssignmentExport<F>>>,
     _marker: PhantomData<F>,
 }
 
@@ -3267,13 +4179,445 @@ impl<F: Field> TxCircuit<F> {
             size: Self::min_num_rows(max_txs, max_calldata),
             chain_id,
             start_l1_queue_index,
+            hasher: TxHasher::default(),
+            recovery_cache: None,
             value_cells: RefCell::new(None),
+            num_txs_cells: RefCell::new(None),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            overrides: HashMap::new(),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            fixed_overrides: HashMap::new(),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            assignment_log: RefCell::new(None),
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            assignment_export: RefCell::new(None),
             _marker: PhantomData,
         }
     }
 
+    /// Returns the sign data of the txs followed by the padding txs, i.e. exactly
+    /// the signatures that the sig circuit must verify so that the sig table
+    /// lookups of the tx circuit succeed. This allows to prove the sig circuit
+    /// separately from the tx circuit.
+    ///
+    /// Proving these with the sig circuit alone is also how to recover the senders without
+    /// the other tx fields: the tx circuit has no reduced tag layout, as its gates rely on
+    /// the `TX_LEN` fixed rows of each tx.
+    pub fn sig_table_inputs(&self) -> Result<Vec<SignData>, TxSignDataError> {
+        get_sign_data(
+            &self.txs,
+            self.max_txs,
+            self.chain_id,
+            &self.hasher,
+            self.recovery_cache.as_ref(),
+        )
+    }
+
+    /// Returns whether each tx is an L1 msg, in the order of the tx table. Padding txs
+    /// are never L1 msgs and are not included.
+    pub fn l1_msg_flags(&self) -> Vec<bool> {
+        self.txs.iter().map(|tx| tx.tx_type.is_l1_msg()).collect()
+    }
+
+    /// Returns the (num_l1_msgs, num_l2_txs) of each block, keyed by block number, i.e.
+    /// the values of the cells exported in `num_txs_cells`. The L1 msgs of a block
+    /// include the ones skipped between the queue indexes of its L1 msg txs.
+    pub fn num_txs_per_block(&self) -> BTreeMap<u64, (u64, u64)> {
+        let mut num_txs_per_block = BTreeMap::new();
+        let mut total_l1_popped_before = self.start_l1_queue_index;
+        for tx in self.txs.iter() {
+            let (num_l1_msgs, num_l2_txs) =
+                num_txs_per_block.entry(tx.block_number).or_insert((0, 0));
+            if tx.tx_type.is_l1_msg() {
+                // tx.nonce is the queue index of an l1 msg
+                *num_l1_msgs += tx.nonce - total_l1_popped_before + 1;
+                total_l1_popped_before = tx.nonce + 1;
+            } else {
+                *num_l2_txs += 1;
+            }
+        }
+        num_txs_per_block
+    }
+
+    /// Returns the hash of each tx, i.e. the raw bytes behind the `TxHash` row of the tx
+    /// table that the PI circuit copies into the public input.
+    pub fn tx_hashes(&self) -> Vec<[u8; 32]> {
+        self.txs
+            .iter()
+            .map(|tx| self.hasher.hash(tx.rlp_hash_stream().as_slice()))
+            .collect()
+    }
+
+    /// Returns all the tx-derived witness that the PI circuit consumes at once.
+    pub fn pi_inputs(&self) -> TxPiInputs {
+        TxPiInputs {
+            num_txs_per_block: self.num_txs_per_block(),
+            tx_hashes: self.tx_hashes(),
+            l1_msg_flags: self.l1_msg_flags(),
+        }
+    }
+
+    /// Returns whether the tx at `tx_index` in the tx table is treated as padding, i.e.
+    /// the `is_padding_tx` it gets assigned: a tx from the zero address is padding just
+    /// like the dummy txs filling the slots after the real txs.
+    pub fn is_padding(&self, tx_index: usize) -> bool {
+        self.txs
+            .get(tx_index)
+            .map_or(true, |tx| tx.caller_address.is_zero())
+    }
+
+    /// Returns the call data bytes of all txs concatenated in the order they are assigned
+    /// to the CallData rows of the dynamic section. Padding txs have no call data.
+    pub fn concatenated_calldata(&self) -> Vec<u8> {
+        self.txs
+            .iter()
+            .flat_map(|tx| tx.call_data.iter().copied())
+            .collect()
+    }
+
+    /// Returns the intrinsic gas of each tx, as charged by BeginTx: the base cost (53000
+    /// for creation txs, 21000 otherwise), the gas cost of the call data under the
+    /// `calldata_gas_costs` of `config` (the `calldata_gas_cost_acc` of its last byte),
+    /// of the access list and, after Shanghai, of the init code words. Padding txs are
+    /// not included.
+    pub fn intrinsic_gas(&self, config: &TxCircuitConfig<F>) -> Vec<u64> {
+        self.txs
+            .iter()
+            .map(|tx| {
+                let base_gas = if tx.is_create {
+                    eth_types::evm_types::GasCost::CREATION_TX
+                } else {
+                    eth_types::evm_types::GasCost::TX
+                };
+                #[cfg(feature = "shanghai")]
+                let init_code_gas = if tx.is_create {
+                    let num_words = (tx.call_data.len() as u64 + 31) / 32;
+                    num_words * eth_types::evm_types::INIT_CODE_WORD_GAS
+                } else {
+                    0
+                };
+                #[cfg(not(feature = "shanghai"))]
+                let init_code_gas = 0;
+
+                base_gas.as_u64()
+                    + config.calldata_gas_costs.data_cost(&tx.call_data)
+                    + tx_access_list_gas_cost(&tx.access_list)
+                    + init_code_gas
+            })
+            .collect()
+    }
+
+    /// Returns the gas price each tx pays under `base_fee`: `min(max_fee_per_gas,
+    /// base_fee + max_priority_fee_per_gas)` for EIP-1559 txs and `gas_price` for the
+    /// others. Prices that don't fit into an u64 are saturated. Padding txs are not
+    /// included.
+    pub fn effective_gas_prices(&self, base_fee: u64) -> Vec<u64> {
+        self.txs
+            .iter()
+            .map(|tx| {
+                let gas_price = if tx.tx_type.is_eip1559() {
+                    min(
+                        tx.max_fee_per_gas,
+                        tx.max_priority_fee_per_gas
+                            .saturating_add(Word::from(base_fee)),
+                    )
+                } else {
+                    tx.gas_price
+                };
+                u64::try_from(gas_price).unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+
+    /// Returns the signing hash of each tx, i.e. the hash of its unsigned RLP encoding
+    /// which is assigned to the `TxSignHash` row of the tx, without the padding txs.
+    pub fn sign_hashes(&self) -> Result<Vec<[u8; 32]>, Error> {
+        Ok(self
+            .txs
+            .iter()
+            .map(|tx| self.hasher.hash(&tx.rlp_unsigned))
+            .collect())
+    }
+
+    /// Returns the output rows of the RLP table that the RLP lookups of the tx circuit
+    /// (over the txs followed by the padding txs) resolve to, i.e. exactly the rows
+    /// that the RLP circuit must produce so that these lookups succeed. This allows to
+    /// check the coverage of an RLP circuit proved separately from the tx circuit.
+    pub fn expected_rlp_rows(&self, challenges: &Challenges<Value<F>>) -> Vec<RlpTableRow<F>> {
+        let keccak_input = challenges.keccak_input();
+        let evm_word = challenges.evm_word();
+        let padding_txs = get_padding_txs(self.txs.len(), self.max_txs, self.chain_id);
+
+        let mut rows = vec![];
+        for tx in self.txs.iter().chain(padding_txs.iter()) {
+            let (hash_format, sign_format) = match tx.tx_type {
+                Eip155 => (TxHashEip155, Some(TxSignEip155)),
+                PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
+                Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
+                Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
+                L1Msg => (L1MsgHash, None),
+            };
+            let row =
+                |format, rlp_tag, tag_value, tag_bytes_rlc, tag_length, is_none| RlpTableRow {
+                    tx_id: tx.id as u64,
+                    format,
+                    rlp_tag,
+                    tag_value,
+                    tag_bytes_rlc,
+                    tag_length,
+                    is_output: true,
+                    is_none,
+                    access_list_idx: 0,
+                    storage_key_idx: 0,
+                };
+
+            // 1. the EIP-2718 type prefix byte of typed txs
+            let type_byte = match tx.tx_type {
+                L1Msg => Some(0x7E),
+                Eip2930 => Some(0x01),
+                Eip1559 => Some(0x02),
+                _ => None,
+            };
+            if let Some(type_byte) = type_byte {
+                let type_byte = Value::known(F::from(type_byte));
+                rows.push(row(
+                    hash_format,
+                    RLPTxType.into(),
+                    type_byte,
+                    type_byte,
+                    1,
+                    false,
+                ));
+            }
+
+            // 2. the fixed rows of the tx
+            for (tx_tag, rlp_input, tx_value) in tx_fixed_rows(tx, &self.hasher, challenges) {
+                let formats = [
+                    sign_format.filter(|_| is_rlp_sign_lookup(tx, tx_tag)),
+                    Some(hash_format).filter(|_| {
+                        is_rlp_hash_lookup(tx, tx_tag) || is_l1_msg_hash_lookup(tx, tx_tag)
+                    }),
+                ];
+                for format in formats.into_iter().flatten() {
+                    rows.push(row(
+                        format,
+                        rlp_input.clone().map_or(Null, |input| input.tag),
+                        tx_value,
+                        rlp_input
+                            .clone()
+                            .map_or(keccak_input.map(|_| F::zero()), |input| input.be_bytes_rlc),
+                        rlp_input.clone().map_or(0, |input| input.be_bytes_len) as usize,
+                        rlp_input.clone().map_or(false, |input| input.is_none),
+                    ));
+                }
+            }
+
+            // 3. the access list addresses and storage keys of the tx
+            let access_list = tx.access_list.as_ref().map_or(&[][..], |al| &al.0[..]);
+            for (al_idx, al) in access_list.iter().enumerate() {
+                let mut items = vec![(
+                    Tag::AccessListAddress,
+                    0,
+                    Value::known(al.address.to_scalar().unwrap()),
+                    al.address.to_fixed_bytes().to_vec(),
+                )];
+                items.extend(al.storage_keys.iter().enumerate().map(|(sk_idx, sk)| {
+                    (
+                        Tag::AccessListStorageKey,
+                        sk_idx + 1,
+                        rlc_be_bytes(&sk.to_fixed_bytes(), evm_word),
+                        sk.to_fixed_bytes().to_vec(),
+                    )
+                }));
+                for (tag, sk_idx, tag_value, be_bytes) in items {
+                    for format in sign_format.into_iter().chain([hash_format]) {
+                        rows.push(RlpTableRow {
+                            access_list_idx: (al_idx + 1) as u64,
+                            storage_key_idx: sk_idx as u64,
+                            ..row(
+                                format,
+                                tag.into(),
+                                tag_value,
+                                rlc_be_bytes(&be_bytes, keccak_input),
+                                be_bytes.len(),
+                                false,
+                            )
+                        });
+                    }
+                }
+            }
+        }
+
+        rows
+    }
+
+    /// Returns a TxCircuit with the same parameters, txs and hasher as the circuit that
+    /// recorded `log`. Synthesizing it records a new log which can be compared
+    /// with `log` to find the first row where the assignments diverge.
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    pub fn from_replay(log: TxAssignmentLog<F>) -> Self {
+        Self {
+            hasher: log.hasher,
+            recovery_cache: log.recovery_cache,
+            ..Self::new(
+                log.max_txs,
+                log.max_calldata,
+                log.chain_id,
+                log.start_l1_queue_index,
+                log.txs,
+            )
+        }
+    }
+
+    /// Returns the tx table assignments recorded by the last synthesis of this circuit.
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    pub fn assignment_log(&self) -> Option<TxAssignmentLog<F>> {
+        self.assignment_log.borrow().clone()
+    }
+
+    /// Returns the values written to the tx circuit region by the last synthesis of this
+    /// circuit.
+    #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+    pub fn assignment_export(&self) -> Option<TxAssignmentExport<F>> {
+        self.assignment_export.borrow().clone()
+    }
+
+    /// Appends the txs of `other` to this circuit, so that circuits built per block
+    /// can be combined without rebuilding them from scratch. The txs of `other` are
+    /// renumbered to follow the txs of this circuit.
+    ///
+    /// Fails if the combined txs exceed the `max_txs` or `max_calldata` budget of this
+    /// circuit, if the chain ids differ, if the block numbers of `other` precede the
+    /// ones of this circuit or if `other` does not start at the L1 queue index where
+    /// this circuit stops.
+    pub fn try_append(&mut self, other: TxCircuit<F>) -> Result<(), Error> {
+        if self.chain_id != other.chain_id {
+            error!(
+                "cannot append tx circuit with chain_id {} to chain_id {}",
+                other.chain_id, self.chain_id
+            );
+            return Err(Error::Synthesis);
+        }
+        let num_txs = self.txs.len() + other.txs.len();
+        if num_txs > self.max_txs {
+            error!("{num_txs} txs exceed max_txs {}", self.max_txs);
+            return Err(Error::Synthesis);
+        }
+        let calldata_len: usize = self
+            .txs
+            .iter()
+            .chain(other.txs.iter())
+            .map(|tx| tx.call_data.len())
+            .sum();
+        if calldata_len > self.max_calldata {
+            error!(
+                "{calldata_len} calldata bytes exceed max_calldata {}",
+                self.max_calldata
+            );
+            return Err(Error::Synthesis);
+        }
+        if let (Some(last), Some(first)) = (self.txs.last(), other.txs.first()) {
+            if first.block_number < last.block_number {
+                error!(
+                    "block number {} of appended txs is less than block number {}",
+                    first.block_number, last.block_number
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+        let end_l1_queue_index = self
+            .txs
+            .iter()
+            .rev()
+            .find(|tx| tx.tx_type.is_l1_msg())
+            .map_or(self.start_l1_queue_index, |tx| tx.nonce + 1);
+        if other.start_l1_queue_index != end_l1_queue_index {
+            error!(
+                "appended txs start at l1 queue index {} instead of {}",
+                other.start_l1_queue_index, end_l1_queue_index
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let id_offset = self.txs.len();
+        self.txs.extend(other.txs.into_iter().map(|mut tx| {
+            tx.id += id_offset;
+            tx
+        }));
+
+        Ok(())
+    }
+
+    /// Returns a circuit over the txs in `range` only, renumbered from 1, with the same
+    /// `max_txs` and `max_calldata` budget. The L1 queue starts after the L1 msgs that
+    /// precede `range`, and num_txs, cum_num_txs and num_all_txs are recomputed over
+    /// the txs in `range`.
+    ///
+    /// If `range` includes a block only partially, the recomputed totals of that block
+    /// don't match the ones of the full block, so the block table lookups of the tx
+    /// circuit fail against the block table of the full chunk. Such a subset is only
+    /// valid together with a block table built from the subset itself.
+    pub fn subset(&self, range: Range<usize>) -> Result<Self, Error> {
+        if range.start > range.end || range.end > self.txs.len() {
+            error!(
+                "tx range {range:?} is out of the {} txs of the circuit",
+                self.txs.len()
+            );
+            return Err(Error::Synthesis);
+        }
+        let same_block = |i: usize, j: usize| match (self.txs.get(i), self.txs.get(j)) {
+            (Some(a), Some(b)) => a.block_number == b.block_number,
+            _ => false,
+        };
+        if !range.is_empty()
+            && ((range.start > 0 && same_block(range.start - 1, range.start))
+                || same_block(range.end - 1, range.end))
+        {
+            warn!("tx range {range:?} includes a block partially");
+        }
+
+        let start_l1_queue_index = self.txs[..range.start]
+            .iter()
+            .rev()
+            .find(|tx| tx.tx_type.is_l1_msg())
+            .map_or(self.start_l1_queue_index, |tx| tx.nonce + 1);
+        let txs = self.txs[range]
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, mut tx)| {
+                tx.id = i + 1;
+                tx
+            })
+            .collect();
+
+        Ok(TxCircuit {
+            hasher: self.hasher.clone(),
+            recovery_cache: self.recovery_cache.clone(),
+            ..TxCircuit::new(
+                self.max_txs,
+                self.max_calldata,
+                self.chain_id,
+                start_l1_queue_index,
+                txs,
+            )
+        })
+    }
+
     /// Returned data contains both the tx hash and sig hash
     fn keccak_inputs(&self) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self
+            .keccak_inputs_with_outputs()?
+            .into_iter()
+            .map(|(input, _)| input)
+            .collect())
+    }
+
+    /// Same as `keccak_inputs`, but each input is paired with its output in the hash
+    /// table: the inputs of `TxHash` and `TxSignHash` are hashed by the hasher of the
+    /// circuit, while the public keys of the sig circuit are always hashed by keccak256
+    /// to get their addresses.
+    pub(crate) fn keccak_inputs_with_outputs(&self) -> Result<Vec<(Vec<u8>, [u8; 32])>, Error> {
         let mut inputs = Vec::new();
 
         let padding_tx = {
@@ -3285,33 +4629,91 @@ impl<F: Field> TxCircuit<F> {
             .txs
             .iter()
             .chain(iter::once(&padding_tx))
-            .map(|tx| tx.rlp_signed.clone())
-            .collect::<Vec<Vec<u8>>>();
+            .map(|tx| (tx.rlp_signed.clone(), self.hasher.hash(&tx.rlp_signed)))
+            .collect::<Vec<_>>();
         inputs.extend_from_slice(&hash_datas);
 
-        let sign_datas: Vec<SignData> = self
-            .txs
-            .iter()
-            .chain(iter::once(&padding_tx))
-            .enumerate()
-            .map(|(_, tx)| {
-                if tx.tx_type.is_l1_msg() {
-                    Ok(SignData::default())
-                } else {
-                    tx.sign_data().map_err(|e| {
-                        error!("keccak_inputs_tx_circuit error: {:?}", e);
-                        Error::Synthesis
-                    })
-                }
-            })
-            .collect::<Result<Vec<SignData>, Error>>()?;
-        // Keccak inputs from SignVerify Chip
-        let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
-        inputs.extend_from_slice(&sign_verify_inputs);
+        let sign_datas = txs_sign_data(
+            self.txs.iter().chain(iter::once(&padding_tx)),
+            &self.hasher,
+            self.recovery_cache.as_ref(),
+        )?;
+        // Keccak inputs from SignVerify Chip, i.e. the public key and the signed message
+        // of each signature
+        for (pk, msg) in keccak_inputs_sign_verify(&sign_datas).into_iter().tuples() {
+            let (pk_hash, msg_hash) = (keccak256(&pk), self.hasher.hash(&msg));
+            inputs.push((pk, pk_hash));
+            inputs.push((msg, msg_hash));
+        }
 
         Ok(inputs)
     }
 
+    /// Returns the number of keccak circuit rows needed to hash the keccak inputs of the
+    /// tx circuit, including the 2 permutations worth of rows the keccak circuit can't use.
+    pub fn keccak_row_estimate(&self) -> Result<usize, Error> {
+        // the padding of an input always takes at least one byte of its last chunk
+        let num_keccak_f = self
+            .keccak_inputs()?
+            .iter()
+            .map(|input| input.len() / RATE + 1)
+            .sum::<usize>();
+        Ok((num_keccak_f + 2) * (NUM_ROUNDS + 1) * get_num_rows_per_round())
+    }
+
+    /// Checks before proving that a keccak circuit of `keccak_rows_available` rows has the
+    /// capacity to hash the keccak inputs of the tx circuit. Other circuits sharing the
+    /// keccak table must be accounted for in `keccak_rows_available`.
+    pub fn assert_keccak_capacity(&self, keccak_rows_available: usize) -> Result<(), Error> {
+        let keccak_rows = self.keccak_row_estimate()?;
+        if keccak_rows > keccak_rows_available {
+            error!(
+                "the keccak inputs of the tx circuit need {keccak_rows} keccak rows, only {keccak_rows_available} are available"
+            );
+            return Err(Error::Synthesis);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of signature verifications the sig circuit needs room for, i.e.
+    /// the minimum `max_verif` of the sig circuit: one for each of the `sig_table_inputs`,
+    /// which has an entry per tx slot, L1 msgs and padding txs included.
+    pub fn sig_circuit_capacity(&self) -> Result<usize, TxSignDataError> {
+        Ok(self.sig_table_inputs()?.len())
+    }
+
+    /// Checks before proving that the L1 msgs of each block precede its L2 txs, as
+    /// required by a tx circuit configured with `check_l1_msgs_first`.
+    pub fn validate_ordering(&self) -> Result<(), Error> {
+        for (prev, tx) in self.txs.iter().tuple_windows() {
+            if prev.block_number == tx.block_number
+                && !prev.tx_type.is_l1_msg()
+                && tx.tx_type.is_l1_msg()
+            {
+                error!(
+                    "L1 msg (tx {}) follows L2 tx {} in block {}",
+                    tx.id, prev.id, tx.block_number
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the smallest `k` such that the tx circuit fits in `2^k` rows, i.e. the
+    /// rows assigned for `max_txs` and `max_calldata` (including the leading null row
+    /// and the padding row after the calldata region) and the u16 table looked up by
+    /// the tx circuit, followed by the `minimum_rows` reserved by `config`.
+    pub fn recommended_k(&self, config: &TxCircuitConfig<F>) -> u32 {
+        // null row + fixed rows of each tx + calldata region + padding row
+        let tx_rows = Self::min_num_rows(self.max_txs, self.max_calldata) + 2;
+        let required_rows = max(tx_rows, 1 << 16);
+        // the reserved rows are at least the minimum rows of the constraint system,
+        // which are the unusable rows, the row of `l_0` and one usable row
+        let reserved_rows = max(config.minimum_rows(), Self::unusable_rows() + 2);
+        log2_ceil(required_rows + reserved_rows)
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
@@ -3415,10 +4817,18 @@ impl<F: Field> TxCircuit<F> {
         start_l1_queue_index: u64,
         sign_datas: Vec<SignData>,
         padding_txs: &[Transaction],
-    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+    ) -> Result<(Vec<AssignedCell<F, F>>, BlockNumTxsCells<F>), Error> {
         layouter.assign_region(
-            || "tx table aux",
+            || TX_REGION_NAME,
             |mut region| {
+                // the floor planner may call this closure more than once, only the last
+                // pass is kept in the assignment log
+                #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+                {
+                    config.assignment_log.borrow_mut().clear();
+                    config.assignment_export.borrow_mut().cells.clear();
+                }
+
                 let mut offset = 0;
 
                 let sigs = &sign_datas;
@@ -3428,11 +4838,20 @@ impl<F: Field> TxCircuit<F> {
                 let mut cum_num_txs = 0;
                 let mut num_txs;
                 let mut num_all_txs_acc = 0;
+                let mut num_l2_txs = 0;
+                let mut num_txs_acc = 0;
                 let mut total_l1_popped_before = start_l1_queue_index;
                 let mut total_l1_popped_after = start_l1_queue_index;
 
                 // 1. Empty entry
-                region.assign_fixed(|| "q_first", config.q_first, 0, || Value::known(F::one()))?;
+                debug_assert_eq!(offset, 0, "q_first is only enabled at the null row");
+                config.assign_fixed(
+                    &mut region,
+                    || "q_first",
+                    config.q_first,
+                    0,
+                    Value::known(F::one()),
+                )?;
                 config.assign_null_row(&mut region, &mut offset)?;
 
                 // 2. Assign all tx fields except for call data
@@ -3445,6 +4864,7 @@ impl<F: Field> TxCircuit<F> {
                 };
 
                 let mut tx_value_cells = vec![];
+                let mut block_num_txs_cells = BTreeMap::new();
                 for (i, sign_data) in sigs.iter().enumerate() {
                     let tx = get_tx(i);
                     let block_num = tx.block_number;
@@ -3464,17 +4884,21 @@ impl<F: Field> TxCircuit<F> {
                             if tx.tx_type.is_l1_msg() {
                                 let queue_index = tx.nonce;
                                 num_all_txs_acc = queue_index - total_l1_popped_before + 1;
+                                num_l2_txs = 0;
                                 total_l1_popped_after = queue_index + 1;
                             } else {
                                 // next tx's total_l1_popped_before do not change
                                 total_l1_popped_after = total_l1_popped_before;
                                 num_all_txs_acc = 1;
+                                num_l2_txs = 1;
                             }
                         };
                         // first tx of all or first tx of next block
                         if i == 0 || tx.block_number != self.txs[i - 1].block_number {
                             init_new_block(tx);
+                            num_txs_acc = 1;
                         } else {
+                            num_txs_acc += 1;
                             // same block
                             if tx.tx_type.is_l1_msg() {
                                 let queue_index = tx.nonce;
@@ -3484,12 +4908,15 @@ impl<F: Field> TxCircuit<F> {
                                 // next tx's total_l1_popped_before do not change
                                 total_l1_popped_after = total_l1_popped_before;
                                 num_all_txs_acc += 1;
+                                num_l2_txs += 1;
                             }
                         }
                     } else {
                         num_txs = 0_u64;
                         // padding_tx is an l2 tx
                         num_all_txs_acc = (i - self.txs.len() + 1) as u64;
+                        num_l2_txs = num_all_txs_acc;
+                        num_txs_acc = num_all_txs_acc;
                     }
                     let is_last_tx = i == (sigs.len() - 1);
                     let next_tx = if is_last_tx {
@@ -3505,23 +4932,31 @@ impl<F: Field> TxCircuit<F> {
                         i,
                         num_all_txs_acc,
                     );
-                    tx_value_cells.extend_from_slice(
-                        config.assign_fixed_rows(
-                            &mut region,
-                            &mut offset,
-                            tx,
-                            sign_data,
-                            next_tx,
-                            total_l1_popped_before,
-                            num_all_txs_acc,
-                            num_txs,
-                            cum_num_txs,
-                            challenges,
-                        )?.as_slice()
-                    );
+                    let (value_cells, num_txs_cells) = config.assign_fixed_rows(
+                        &mut region,
+                        &mut offset,
+                        tx,
+                        sign_data,
+                        next_tx,
+                        total_l1_popped_before,
+                        num_all_txs_acc,
+                        num_l2_txs,
+                        num_txs_acc,
+                        num_txs,
+                        cum_num_txs,
+                        &self.hasher,
+                        challenges,
+                    )?;
+                    tx_value_cells.extend_from_slice(value_cells.as_slice());
+                    // the cells of the last tx of a block hold the block's totals
+                    if i < self.txs.len() {
+                        block_num_txs_cells.insert(block_num, num_txs_cells);
+                    }
                     // set next tx's total_l1_popped_before
                     total_l1_popped_before = total_l1_popped_after;
                 }
+                // The tag column is fixed, so the TX_LEN rows per tx (real or padding) are
+                // part of the verifying key and don't rely on this check.
                 assert_eq!(offset, self.max_txs * TX_LEN + 1);
 
                 let calldata_first_row = self.max_txs * TX_LEN + 1;
@@ -3550,6 +4985,7 @@ impl<F: Field> TxCircuit<F> {
                     )?;
                 }
                 assert!(offset <= calldata_last_row, "{offset}, {calldata_last_row}");
+                config.assign_tx_id_diff_limbs(&mut region, calldata_first_row, &self.txs)?;
                 // 3.2 pad calldata with zeros
                 config.assign_calldata_zeros(
                     &mut region,
@@ -3561,10 +4997,40 @@ impl<F: Field> TxCircuit<F> {
                     ("q_calldata_first", config.q_calldata_first, calldata_first_row),
                     ("q_calldata_last", config.q_calldata_last, calldata_last_row-1),
                 ] {
-                    region.assign_fixed(|| col_anno, col, row, || Value::known(F::one()))?;
+                    config.assign_fixed(
+                        &mut region,
+                        || col_anno,
+                        col,
+                        row,
+                        Value::known(F::one()),
+                    )?;
+                }
+                // 3.4. the row after the calldata region is padding
+                config.assign_paddings(&mut region, calldata_last_row, calldata_last_row + 1)?;
+
+                #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+                {
+                    for ((column, offset), &f) in self.overrides.iter() {
+                        config.assign_advice(
+                            &mut region,
+                            || "override",
+                            column.value(config),
+                            *offset,
+                            Value::known(f),
+                        )?;
+                    }
+                    for ((column, offset), &f) in self.fixed_overrides.iter() {
+                        config.assign_fixed(
+                            &mut region,
+                            || "fixed override",
+                            column.value(config),
+                            *offset,
+                            Value::known(f),
+                        )?;
+                    }
                 }
 
-                Ok(tx_value_cells)
+                Ok((tx_value_cells, block_num_txs_cells))
             },
         )
     }
@@ -3618,31 +5084,38 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         config: &Self::Config,
         challenges: &crate::util::Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // the range tables are external tables loaded by the caller, which knows whether
+        // they are loaded
+        self.synthesize_with_challenges(config, challenges, None, layouter)
+    }
+}
+
+impl<F: Field> TxCircuit<F> {
+    /// Make the assignments to the TxCircuit with the given challenges. Unlike
+    /// `synthesize_sub`, the challenges are not required to come from the layouter,
+    /// so that tooling can fix them to reproduce the exact witness values. Fails if
+    /// `range_table_rows` (returned by the loads of the u8 and u16 tables) are given and
+    /// don't cover the full ranges looked up by the tx circuit.
+    pub fn synthesize_with_challenges(
+        &self,
+        config: &TxCircuitConfig<F>,
+        challenges: &crate::util::Challenges<Value<F>>,
+        range_table_rows: Option<RangeTableRows>,
+        layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         assert!(self.txs.len() <= self.max_txs);
+        if let Some(range_table_rows) = range_table_rows {
+            config
+                .u8_table
+                .assert_full_range(range_table_rows.u8_rows)?;
+            config
+                .u16_table
+                .assert_full_range(range_table_rows.u16_rows)?;
+        }
 
-        let padding_txs = (self.txs.len()..self.max_txs)
-            .map(|i| {
-                let mut tx = Transaction::dummy(self.chain_id);
-                tx.id = i + 1;
-                tx
-            })
-            .collect::<Vec<Transaction>>();
-        let sign_datas: Vec<SignData> = self
-            .txs
-            .iter()
-            .chain(padding_txs.iter())
-            .map(|tx| {
-                if tx.tx_type.is_l1_msg() {
-                    Ok(SignData::default())
-                } else {
-                    tx.sign_data().map_err(|e| {
-                        error!("tx_to_sign_data error for tx {:?}", e);
-                        Error::Synthesis
-                    })
-                }
-            })
-            .collect::<Result<Vec<SignData>, Error>>()?;
+        let padding_txs = get_padding_txs(self.txs.len(), self.max_txs, self.chain_id);
+        let sign_datas = self.sig_table_inputs()?;
 
         // check if tx.caller_address == recovered_pk
         let recovered_pks = keccak_inputs_sign_verify(&sign_datas)
@@ -3669,7 +5142,7 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             }
         }
 
-        let tx_value_cells = self.assign(
+        let (tx_value_cells, block_num_txs_cells) = self.assign(
             config,
             challenges,
             layouter,
@@ -3679,40 +5152,550 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         )?;
         // export tx value cells
         *self.value_cells.borrow_mut() = Some(tx_value_cells);
+        *self.num_txs_cells.borrow_mut() = Some(block_num_txs_cells);
+
+        #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+        {
+            *self.assignment_log.borrow_mut() = Some(TxAssignmentLog {
+                max_txs: self.max_txs,
+                max_calldata: self.max_calldata,
+                chain_id: self.chain_id,
+                start_l1_queue_index: self.start_l1_queue_index,
+                txs: self.txs.clone(),
+                hasher: self.hasher.clone(),
+                recovery_cache: self.recovery_cache.clone(),
+                rows: config.assignment_log.borrow().clone(),
+            });
+            *self.assignment_export.borrow_mut() = Some(config.assignment_export.borrow().clone());
+        }
 
         Ok(())
     }
 }
 
-pub(crate) fn get_sign_data(
-    txs: &[Transaction],
-    max_txs: usize,
-    chain_id: usize,
-) -> Result<Vec<SignData>, halo2_proofs::plonk::Error> {
-    let padding_txs = (txs.len()..max_txs)
+/// Returns the padding txs which fill the tx circuit from `num_txs` up to `max_txs`.
+pub(crate) fn get_padding_txs(num_txs: usize, max_txs: usize, chain_id: u64) -> Vec<Transaction> {
+    (num_txs..max_txs)
         .map(|i| {
-            let mut tx = Transaction::dummy(chain_id as u64);
+            let mut tx = Transaction::dummy(chain_id);
             tx.id = i + 1;
             tx
         })
-        .collect::<Vec<Transaction>>();
-    let signatures: Vec<SignData> = txs
-        .iter()
-        .chain(padding_txs.iter())
-        .map(|tx| {
+        .collect()
+}
+
+/// Error returned when the sign data of a tx can't be computed, e.g. because
+/// its signature is malformed.
+#[derive(Debug)]
+pub struct TxSignDataError {
+    /// Index of the tx (including padding txs) in the tx circuit
+    pub index: usize,
+    /// Hash of the tx
+    pub hash: H256,
+    /// The underlying error
+    pub cause: eth_types::Error,
+}
+
+impl std::fmt::Display for TxSignDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "tx_to_sign_data error for tx #{} (hash {:?}): {:?}",
+            self.index, self.hash, self.cause
+        )
+    }
+}
+
+impl From<TxSignDataError> for Error {
+    fn from(e: TxSignDataError) -> Self {
+        error!("{e}");
+        Error::Synthesis
+    }
+}
+
+/// Returns the sign data of each tx. L1 msgs are not signed and get a dummy signature.
+/// The public keys are taken from `recovery_cache` when they have already been recovered.
+fn txs_sign_data<'a>(
+    txs: impl Iterator<Item = &'a Transaction>,
+    hasher: &TxHasher,
+    recovery_cache: Option<&RecoveryCache>,
+) -> Result<Vec<SignData>, TxSignDataError> {
+    txs.enumerate()
+        .map(|(index, tx)| {
             if tx.tx_type.is_l1_msg() {
                 // dummy signature
                 Ok(SignData::default())
             } else {
-                // TODO: map err or still use bus_mapping::err?
-                tx.sign_data().map_err(|e| {
-                    log::error!("tx_to_sign_data error for tx {:?}", e);
-                    halo2_proofs::plonk::Error::Synthesis
-                })
+                tx.sign_data_with_cache(|msg| hasher.hash(msg), recovery_cache)
+                    .map_err(|cause| TxSignDataError {
+                        index,
+                        hash: tx.hash,
+                        cause,
+                    })
             }
         })
-        .collect::<Result<Vec<SignData>, halo2_proofs::plonk::Error>>()?;
-    Ok(signatures)
+        .collect()
+}
+
+/// Returns the sign data of `txs` followed by the padding txs, i.e. the same signatures
+/// that are assigned by the tx circuit.
+pub(crate) fn get_sign_data(
+    txs: &[Transaction],
+    max_txs: usize,
+    chain_id: u64,
+    hasher: &TxHasher,
+    recovery_cache: Option<&RecoveryCache>,
+) -> Result<Vec<SignData>, TxSignDataError> {
+    let padding_txs = get_padding_txs(txs.len(), max_txs, chain_id);
+    txs_sign_data(txs.iter().chain(padding_txs.iter()), hasher, recovery_cache)
+}
+
+/// Returns the (tx field tag, RLP table input, value) of the TX_LEN fixed rows of `tx`.
+fn tx_fixed_rows<F: Field>(
+    tx: &Transaction,
+    hasher: &TxHasher,
+    challenges: &Challenges<Value<F>>,
+) -> Vec<(TxFieldTag, Option<RlpTableInputValue<F>>, Value<F>)> {
+    let keccak_input = challenges.keccak_input();
+    let evm_word = challenges.evm_word();
+    let zero_rlc = keccak_input.map(|_| F::zero());
+    // the same stream of bytes is decoded by the RLP circuit
+    let rlp_signed = tx.rlp_hash_stream().as_slice();
+    let sign_hash = hasher.hash(tx.rlp_unsigned.as_slice());
+    let hash = hasher.hash(rlp_signed);
+    let sign_hash_rlc = rlc_be_bytes(&sign_hash, evm_word);
+    let hash_rlc = rlc_be_bytes(&hash, evm_word);
+    let rlp_sign_tag_length = if tx.tx_type.is_l1_msg() {
+        // l1 msg does not have sign data
+        0
+    } else {
+        get_rlp_len_tag_length(&tx.rlp_unsigned)
+    };
+    // l1 msg has no chain id in its hash
+    let chain_id = if tx.tx_type.is_l1_msg() {
+        0
+    } else {
+        tx.chain_id
+    };
+    let (access_list_address_size, access_list_storage_key_size) =
+        access_list_size(&tx.access_list);
+
+    // fixed_rows of a tx
+    vec![
+        // need to be in same order as that tx table load function uses
+        (
+            Nonce, // tx field tag
+            Some(RlpTableInputValue {
+                tag: Tag::Nonce.into(),
+                is_none: tx.nonce == 0,
+                be_bytes_len: tx.nonce.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.nonce.to_be_bytes(), keccak_input),
+            }),
+            Value::known(F::from(tx.nonce)),
+        ),
+        (
+            GasPrice,
+            Some(RlpTableInputValue {
+                tag: Tag::GasPrice.into(),
+                is_none: tx.gas_price.is_zero(),
+                be_bytes_len: tx.gas_price.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.gas_price.to_be_bytes(), keccak_input),
+            }),
+            rlc_be_bytes(&tx.gas_price.to_be_bytes(), evm_word),
+        ),
+        (
+            Gas,
+            Some(RlpTableInputValue {
+                tag: Tag::Gas.into(),
+                is_none: tx.gas == 0,
+                be_bytes_len: tx.gas.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.gas.to_be_bytes(), keccak_input),
+            }),
+            Value::known(F::from(tx.gas)),
+        ),
+        (
+            CallerAddress,
+            Some(RlpTableInputValue {
+                tag: Tag::Sender.into(),
+                is_none: false,
+                be_bytes_len: tx.caller_address.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.caller_address.to_fixed_bytes(), keccak_input),
+            }),
+            Value::known(tx.caller_address.to_scalar().expect("tx.from too big")),
+        ),
+        (
+            CalleeAddress,
+            Some(RlpTableInputValue {
+                tag: Tag::To.into(),
+                is_none: tx.callee_address.is_none(),
+                be_bytes_len: tx.callee_address.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(
+                    tx.callee_address
+                        .map_or(vec![], |callee| callee.to_fixed_bytes().to_vec())
+                        .as_slice(),
+                    keccak_input,
+                ),
+            }),
+            Value::known(
+                tx.callee_address
+                    .unwrap_or(Address::zero())
+                    .to_scalar()
+                    .expect("tx.to too big"),
+            ),
+        ),
+        (IsCreate, None, Value::known(F::from(tx.is_create as u64))),
+        (
+            TxFieldTag::Value,
+            Some(RlpTableInputValue {
+                tag: Tag::Value.into(),
+                is_none: tx.value.is_zero(),
+                be_bytes_len: tx.value.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.value.to_be_bytes(), keccak_input),
+            }),
+            rlc_be_bytes(&tx.value.to_be_bytes(), evm_word),
+        ),
+        (
+            CallDataRLC,
+            Some(RlpTableInputValue {
+                tag: Tag::Data.into(),
+                is_none: tx.call_data.is_empty(),
+                be_bytes_len: tx.call_data.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.call_data, keccak_input),
+            }),
+            rlc_be_bytes(&tx.call_data, keccak_input),
+        ),
+        (
+            CallDataLength,
+            None,
+            Value::known(F::from(tx.call_data.len() as u64)),
+        ),
+        (
+            CallDataGasCost,
+            None,
+            Value::known(F::from(tx.call_data_gas_cost)),
+        ),
+        (
+            TxDataGasCost,
+            Some(RlpTableInputValue {
+                tag: GasCost,
+                is_none: false,
+                be_bytes_len: 0,
+                be_bytes_rlc: zero_rlc,
+            }),
+            Value::known(F::from(tx.tx_data_gas_cost)),
+        ),
+        (
+            ChainID,
+            Some(RlpTableInputValue {
+                tag: Tag::ChainId.into(),
+                is_none: chain_id.is_zero(),
+                be_bytes_len: chain_id.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&chain_id.to_be_bytes(), keccak_input),
+            }),
+            Value::known(F::from(chain_id)),
+        ),
+        (
+            SigV,
+            Some(RlpTableInputValue {
+                tag: Tag::SigV.into(),
+                is_none: tx.v.is_zero(),
+                be_bytes_len: tx.v.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.v.to_be_bytes(), keccak_input),
+            }),
+            Value::known(F::from(tx.v)),
+        ),
+        (
+            SigR,
+            Some(RlpTableInputValue {
+                tag: Tag::SigR.into(),
+                is_none: tx.r.is_zero(),
+                be_bytes_len: tx.r.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.r.to_be_bytes(), keccak_input),
+            }),
+            rlc_be_bytes(&tx.r.to_be_bytes(), evm_word),
+        ),
+        (
+            SigS,
+            Some(RlpTableInputValue {
+                tag: Tag::SigS.into(),
+                is_none: tx.s.is_zero(),
+                be_bytes_len: tx.s.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.s.to_be_bytes(), keccak_input),
+            }),
+            rlc_be_bytes(&tx.s.to_be_bytes(), evm_word),
+        ),
+        (
+            TxSignLength,
+            Some(RlpTableInputValue {
+                tag: Len,
+                is_none: false,
+                be_bytes_len: rlp_sign_tag_length,
+                be_bytes_rlc: zero_rlc,
+            }),
+            Value::known(F::from(tx.rlp_unsigned.len() as u64)),
+        ),
+        (
+            TxSignRLC,
+            Some(RlpTableInputValue {
+                tag: RLC,
+                is_none: false,
+                be_bytes_len: 0,
+                be_bytes_rlc: zero_rlc,
+            }),
+            rlc_be_bytes(&tx.rlp_unsigned, keccak_input),
+        ),
+        (TxSignHash, None, sign_hash_rlc),
+        (
+            TxHashLength,
+            Some(RlpTableInputValue {
+                tag: Len,
+                is_none: false,
+                be_bytes_len: get_rlp_len_tag_length(rlp_signed),
+                be_bytes_rlc: zero_rlc,
+            }),
+            Value::known(F::from(rlp_signed.len() as u64)),
+        ),
+        (
+            TxHashRLC,
+            Some(RlpTableInputValue {
+                tag: RLC,
+                is_none: false,
+                be_bytes_len: 0,
+                be_bytes_rlc: zero_rlc,
+            }),
+            rlc_be_bytes(rlp_signed, keccak_input),
+        ),
+        (TxFieldTag::TxHash, None, hash_rlc),
+        (
+            TxFieldTag::TxType,
+            None,
+            Value::known(F::from(tx.tx_type as u64)),
+        ),
+        (
+            AccessListAddressesLen,
+            None,
+            Value::known(F::from(access_list_address_size)),
+        ),
+        (
+            AccessListStorageKeysLen,
+            None,
+            Value::known(F::from(access_list_storage_key_size)),
+        ),
+        (
+            AccessListRLC,
+            Some(RlpTableInputValue {
+                tag: RLC,
+                is_none: false,
+                be_bytes_len: 0,
+                be_bytes_rlc: zero_rlc,
+            }),
+            access_list_rlc(&tx.access_list, challenges),
+        ),
+        (
+            MaxFeePerGas,
+            Some(RlpTableInputValue {
+                tag: Tag::MaxFeePerGas.into(),
+                is_none: tx.max_fee_per_gas.is_zero(),
+                be_bytes_len: tx.max_fee_per_gas.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(&tx.max_fee_per_gas.to_be_bytes(), keccak_input),
+            }),
+            rlc_be_bytes(&tx.max_fee_per_gas.to_be_bytes(), evm_word),
+        ),
+        (
+            MaxPriorityFeePerGas,
+            Some(RlpTableInputValue {
+                tag: Tag::MaxPriorityFeePerGas.into(),
+                is_none: tx.max_priority_fee_per_gas.is_zero(),
+                be_bytes_len: tx.max_priority_fee_per_gas.tag_length(),
+                be_bytes_rlc: rlc_be_bytes(
+                    &tx.max_priority_fee_per_gas.to_be_bytes(),
+                    keccak_input,
+                ),
+            }),
+            rlc_be_bytes(&tx.max_priority_fee_per_gas.to_be_bytes(), evm_word),
+        ),
+        (BlockNumber, None, Value::known(F::from(tx.block_number))),
+    ]
+}
+/// Whether the fixed row of `tx` with tag `tx_tag` is looked up in the RLP table
+/// with the sign format of `tx` (non L1 msg).
+fn is_rlp_sign_lookup(tx: &Transaction, tx_tag: TxFieldTag) -> bool {
+    let is_l1_msg = tx.tx_type.is_l1_msg();
+    let sign_set = [
+        Nonce,
+        Gas,
+        CalleeAddress,
+        TxFieldTag::Value,
+        CallDataRLC,
+        TxSignLength,
+        TxSignRLC,
+    ];
+    let is_tag_in_set = sign_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
+    let case1 = is_tag_in_set && !is_l1_msg;
+    let case2 = !tx.tx_type.is_pre_eip155() && !is_l1_msg && (tx_tag == ChainID);
+    let case3 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
+    let case4 =
+        tx.tx_type.is_eip1559() && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+    case1 || case2 || case3 || case4
+}
+
+/// Whether the fixed row of `tx` with tag `tx_tag` is looked up in the RLP table
+/// with the hash format of `tx` (non L1 msg).
+fn is_rlp_hash_lookup(tx: &Transaction, tx_tag: TxFieldTag) -> bool {
+    let is_l1_msg = tx.tx_type.is_l1_msg();
+    let hash_set = [
+        Nonce,
+        Gas,
+        CalleeAddress,
+        TxFieldTag::Value,
+        CallDataRLC,
+        TxDataGasCost,
+        SigV,
+        SigR,
+        SigS,
+        TxHashLength,
+        TxHashRLC,
+    ];
+    let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
+    let case1 = is_tag_in_set && !is_l1_msg;
+    let case2 = !tx.tx_type.is_eip1559() && !is_l1_msg && (tx_tag == GasPrice);
+    let case3 =
+        tx.tx_type.is_eip1559() && (tx_tag == MaxFeePerGas || tx_tag == MaxPriorityFeePerGas);
+    case1 || case2 || case3
+}
+
+/// Whether the fixed row of `tx` with tag `tx_tag` is looked up in the RLP table
+/// with the hash format of L1 msgs.
+fn is_l1_msg_hash_lookup(tx: &Transaction, tx_tag: TxFieldTag) -> bool {
+    let hash_set = [
+        Nonce,
+        Gas,
+        CalleeAddress,
+        TxFieldTag::Value,
+        CallDataRLC,
+        CallerAddress,
+        TxHashLength,
+        TxHashRLC,
+    ];
+    let is_tag_in_set = hash_set.into_iter().filter(|tag| tx_tag == *tag).count() == 1;
+    tx.tx_type.is_l1_msg() && is_tag_in_set
+}
+
+/// Reference for the lookup conditions of the fixed row of `tx` with tag `tx_tag`. It
+/// lists the tags looked up for the type of `tx` instead of deciding tag by tag, so that
+/// it can be checked against the conditions assigned by `assign_fixed_rows`.
+#[cfg(debug_assertions)]
+fn reference_lookup_conditions(
+    tx: &Transaction,
+    tx_tag: TxFieldTag,
+) -> BTreeMap<LookupCondition, bool> {
+    let is_l1_msg = tx.tx_type.is_l1_msg();
+    let fee_tags = if tx.tx_type.is_eip1559() {
+        vec![MaxFeePerGas, MaxPriorityFeePerGas]
+    } else {
+        vec![GasPrice]
+    };
+    let (mut sign_tags, mut hash_tags, mut l1_msg_hash_tags) = (vec![], vec![], vec![]);
+    if is_l1_msg {
+        l1_msg_hash_tags.extend([
+            Nonce,
+            Gas,
+            CalleeAddress,
+            TxFieldTag::Value,
+            CallDataRLC,
+            CallerAddress,
+            TxHashLength,
+            TxHashRLC,
+        ]);
+    } else {
+        sign_tags.extend([
+            Nonce,
+            Gas,
+            CalleeAddress,
+            TxFieldTag::Value,
+            CallDataRLC,
+            TxSignLength,
+            TxSignRLC,
+        ]);
+        sign_tags.extend(fee_tags.iter().copied());
+        if !tx.tx_type.is_pre_eip155() {
+            sign_tags.push(ChainID);
+        }
+        hash_tags.extend([
+            Nonce,
+            Gas,
+            CalleeAddress,
+            TxFieldTag::Value,
+            CallDataRLC,
+            TxDataGasCost,
+            SigV,
+            SigR,
+            SigS,
+            TxHashLength,
+            TxHashRLC,
+        ]);
+        hash_tags.extend(fee_tags);
+    }
+    let has_access_list = tx
+        .access_list
+        .as_ref()
+        .map_or(false, |access_list| !access_list.0.is_empty());
+
+    BTreeMap::from([
+        (
+            LookupCondition::TxCalldata,
+            tx_tag == CallDataLength && !tx.call_data.is_empty(),
+        ),
+        (
+            LookupCondition::TxAccessList,
+            tx_tag == AccessListAddressesLen && has_access_list,
+        ),
+        (LookupCondition::RlpSignTag, sign_tags.contains(&tx_tag)),
+        (LookupCondition::RlpHashTag, hash_tags.contains(&tx_tag)),
+        (
+            LookupCondition::L1MsgHash,
+            l1_msg_hash_tags.contains(&tx_tag),
+        ),
+        (
+            LookupCondition::Keccak,
+            tx_tag == TxHashLength || (tx_tag == TxSignLength && !is_l1_msg),
+        ),
+    ])
+}
+
+/// Panics if the lookup conditions assigned at the fixed row of `tx` with tag `tx_tag`
+/// differ from [`reference_lookup_conditions`].
+#[cfg(debug_assertions)]
+fn assert_lookup_conditions<F: Field>(
+    tx: &Transaction,
+    tx_tag: TxFieldTag,
+    conditions: &BTreeMap<LookupCondition, F>,
+) {
+    let reference = reference_lookup_conditions(tx, tx_tag);
+    assert!(
+        conditions.keys().eq(reference.keys()),
+        "lookup conditions of tx {} at tag {tx_tag:?} differ from the reference",
+        tx.id
+    );
+    for (condition, value) in conditions {
+        assert!(
+            *value == F::from(reference[condition] as u64),
+            "lookup condition {condition:?} of tx {} at tag {tx_tag:?} differs from the reference",
+            tx.id
+        );
+    }
+}
+
+/// Returns true if `be_bytes_rlc` is the RLC (under `rand`) of the big-endian bytes of the
+/// integer `value`. Leading zero bytes don't change the RLC, so the full 32-byte
+/// representation of `value` is used.
+fn be_bytes_rlc_matches_value<F: Field>(value: F, be_bytes_rlc: F, rand: F) -> bool {
+    let mut be_bytes = value.to_repr();
+    be_bytes.reverse();
+    let expected = be_bytes
+        .iter()
+        .fold(F::zero(), |acc, byte| acc * rand + F::from(*byte as u64));
+    expected == be_bytes_rlc
 }
 
 /// Returns the RLC of the access list including addresses and storage keys
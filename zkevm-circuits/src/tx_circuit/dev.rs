@@ -1,23 +1,203 @@
 /// TxCircuitTester is the combined circuit of tx circuit and sig circuit.
-use std::marker::PhantomData;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Debug},
+    io,
+    marker::PhantomData,
+    ops::Range,
+};
 
-use super::get_sign_data;
 pub use super::TxCircuit;
+use super::{get_padding_txs, TX_REGION_NAME};
 
 use crate::{
     sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
     table::{
-        BlockTable, KeccakTable, RlpFsmRlpTable as RlpTable, SigTable, TxTable, U16Table, U8Table,
+        BlockTable, KeccakTable, RlpFsmRlpTable as RlpTable, SigTable, TxFieldTag, TxTable,
+        U16Table, U8Table,
+    },
+    tx_circuit::{
+        CalldataGasCosts, RangeTableRows, TxCircuitConfig, TxCircuitConfigArgs, TxHasher,
     },
-    tx_circuit::{TxCircuitConfig, TxCircuitConfigArgs},
     util::{Challenges, SubCircuit, SubCircuitConfig},
-    witness::Transaction,
+    witness::{RecoveryCache, Transaction},
 };
 use eth_types::Field;
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner},
-    plonk::{Circuit, ConstraintSystem, Error, Expression},
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{FailureLocation, VerifyFailure},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed},
 };
+use itertools::Itertools;
+
+/// A tx table row written by `TxCircuit::assign`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxTableAssignment<F: Field> {
+    /// Offset of the row in the tx circuit region
+    pub offset: usize,
+    /// Tag of the row
+    pub tag: TxFieldTag,
+    /// Tx id of the row
+    pub tx_id: usize,
+    /// Index of the row
+    pub index: u64,
+    /// Value of the row, `None` if it is unknown (e.g. during keygen)
+    pub value: Option<F>,
+}
+
+/// Replayable log of the tx table rows written by the synthesis of a TxCircuit,
+/// together with the parameters and txs needed to reconstruct the circuit.
+#[derive(Clone, Debug, Default)]
+pub struct TxAssignmentLog<F: Field> {
+    /// Max number of supported transactions
+    pub max_txs: usize,
+    /// Max number of supported calldata bytes
+    pub max_calldata: usize,
+    /// Chain ID
+    pub chain_id: u64,
+    /// Start L1 Queue Index
+    pub start_l1_queue_index: u64,
+    /// List of Transactions
+    pub txs: Vec<Transaction>,
+    /// Hash function behind TxSignHash and TxHash
+    pub hasher: TxHasher,
+    /// Public keys already recovered from tx signatures
+    pub recovery_cache: Option<RecoveryCache>,
+    /// Tx table rows in assignment order
+    pub rows: Vec<TxTableAssignment<F>>,
+}
+
+impl<F: Field> TxAssignmentLog<F> {
+    /// Returns the position in `rows` of the first assignment which differs
+    /// between the two logs, or `None` if they are identical.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        self.rows
+            .iter()
+            .zip(other.rows.iter())
+            .position(|(a, b)| a != b)
+            .or_else(|| {
+                (self.rows.len() != other.rows.len())
+                    .then_some(self.rows.len().min(other.rows.len()))
+            })
+    }
+
+    /// Returns the tx table row written at `offset` of the tx circuit region.
+    pub fn row_at(&self, offset: usize) -> Option<&TxTableAssignment<F>> {
+        self.rows
+            .binary_search_by_key(&offset, |row| row.offset)
+            .ok()
+            .map(|i| &self.rows[i])
+    }
+
+    /// Describes each of the MockProver `failures`, together with the tx id and tag of
+    /// the tx table row it happens at.
+    pub fn annotate_failures(&self, failures: &[VerifyFailure]) -> Vec<String> {
+        failures
+            .iter()
+            .map(|failure| {
+                let location = match failure {
+                    VerifyFailure::ConstraintNotSatisfied { location, .. }
+                    | VerifyFailure::Lookup { location, .. }
+                    | VerifyFailure::Permutation { location, .. } => Some(location),
+                    _ => None,
+                };
+                let row = location.and_then(|location| match location {
+                    FailureLocation::InRegion { region, offset }
+                        if format!("{region}").contains(&format!("'{TX_REGION_NAME}'")) =>
+                    {
+                        self.row_at(*offset)
+                    }
+                    _ => None,
+                });
+                match row {
+                    Some(row) => format!(
+                        "{failure} [tx_id: {}, tag: {:?}, index: {}]",
+                        row.tx_id, row.tag, row.index
+                    ),
+                    None => format!("{failure} [not in the tx table]"),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Column of a value in a [`TxAssignmentExport`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TxAssignmentColumn {
+    /// Advice column with the given index
+    Advice(usize),
+    /// Fixed column with the given index
+    Fixed(usize),
+}
+
+impl fmt::Display for TxAssignmentColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Advice(index) => write!(f, "advice[{index}]"),
+            Self::Fixed(index) => write!(f, "fixed[{index}]"),
+        }
+    }
+}
+
+/// Values written by the synthesis of a TxCircuit to the advice and fixed columns of the
+/// tx circuit region, e.g. for differential testing of the witness generation against
+/// another implementation. The columns assigned inside of the gadget chips (e.g. the
+/// inverses of the is_zero chips) are not included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxAssignmentExport<F: Field> {
+    /// Value of each assigned (offset, column), `None` if it is unknown (e.g. during keygen)
+    pub cells: BTreeMap<(usize, TxAssignmentColumn), Option<F>>,
+}
+
+impl<F: Field> TxAssignmentExport<F> {
+    /// Returns the assigned columns, advice columns first.
+    pub fn columns(&self) -> Vec<TxAssignmentColumn> {
+        self.cells
+            .keys()
+            .map(|(_, column)| *column)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the offsets with at least one assigned column, in increasing order.
+    pub fn offsets(&self) -> Vec<usize> {
+        self.cells
+            .keys()
+            .map(|(offset, _)| *offset)
+            .dedup()
+            .collect()
+    }
+
+    /// Returns the export of the values assigned at `offsets` only.
+    pub fn with_offsets(&self, offsets: Range<usize>) -> Self {
+        let cells = self
+            .cells
+            .range((offsets.start, TxAssignmentColumn::Advice(0))..)
+            .take_while(|((offset, _), _)| *offset < offsets.end)
+            .map(|(key, value)| (*key, *value))
+            .collect();
+
+        Self { cells }
+    }
+
+    /// Writes the export as CSV with a header line followed by a line per offset. A value
+    /// is empty if it is unknown or not assigned at the offset.
+    pub fn write_csv(&self, mut writer: impl io::Write) -> io::Result<()> {
+        let columns = self.columns();
+        writeln!(writer, "offset,{}", columns.iter().join(","))?;
+        for offset in self.offsets() {
+            let values = columns
+                .iter()
+                .map(|column| match self.cells.get(&(offset, *column)) {
+                    Some(Some(value)) => format!("{value:?}"),
+                    _ => String::new(),
+                });
+            writeln!(writer, "{offset},{}", values.join(","))?;
+        }
+        Ok(())
+    }
+}
 
 /// Circuit configuration arguments
 pub struct TxCircuitTesterConfigArgs<F: Field> {
@@ -37,12 +217,73 @@ pub struct TxCircuitTesterConfigArgs<F: Field> {
     pub u16_table: U16Table,
     /// Challenges
     pub challenges: Challenges<Expression<F>>,
+    /// Whether the tx circuit looks up the block table
+    pub enable_block_table_lookups: bool,
+    /// Maximum gas of a tx, if checked by the tx circuit
+    pub max_tx_gas: Option<u64>,
+    /// Whether the tx circuit checks that the L1 msgs of each block precede its L2 txs
+    pub check_l1_msgs_first: bool,
+    /// Gas costs of the call data bytes
+    pub calldata_gas_costs: CalldataGasCosts,
+    /// Rows reserved at the end of the circuit in addition to `meta.minimum_rows()`
+    pub extra_minimum_rows: usize,
+}
+
+impl<F: Field> TxCircuitTesterConfigArgs<F> {
+    /// Construct the tables and challenges of the tester circuit, with the options of
+    /// the tx circuit set as in the super circuit.
+    pub fn construct(meta: &mut ConstraintSystem<F>) -> (Self, Challenges) {
+        let block_table = BlockTable::construct(meta);
+        let tx_table = TxTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let rlp_table = RlpTable::construct(meta);
+        let sig_table = SigTable::construct(meta);
+        let u8_table = U8Table::construct(meta);
+        let u16_table = U16Table::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let args = Self {
+            tx_table,
+            block_table,
+            rlp_table,
+            keccak_table,
+            sig_table,
+            u8_table,
+            u16_table,
+            challenges: challenges.exprs(meta),
+            enable_block_table_lookups: true,
+            max_tx_gas: None,
+            check_l1_msgs_first: false,
+            calldata_gas_costs: CalldataGasCosts::default(),
+            extra_minimum_rows: 0,
+        };
+
+        (args, challenges)
+    }
+
+    /// Configuration arguments of the tx circuit alone
+    pub(super) fn tx_config_args(self) -> TxCircuitConfigArgs<F> {
+        TxCircuitConfigArgs {
+            sig_table: self.sig_table,
+            block_table: self.block_table,
+            tx_table: self.tx_table,
+            keccak_table: self.keccak_table,
+            rlp_table: self.rlp_table,
+            u8_table: self.u8_table,
+            u16_table: self.u16_table,
+            challenges: self.challenges,
+            enable_block_table_lookups: self.enable_block_table_lookups,
+            max_tx_gas: self.max_tx_gas,
+            check_l1_msgs_first: self.check_l1_msgs_first,
+            calldata_gas_costs: self.calldata_gas_costs,
+        }
+    }
 }
 
 /// TxCircuitTesterConfig
 #[derive(Clone, Debug)]
 pub struct TxCircuitTesterConfig<F: Field> {
-    tx_config: TxCircuitConfig<F>,
+    pub(super) tx_config: TxCircuitConfig<F>,
     // SigTable is assigned inside SigCircuit
     sig_config: SigCircuitConfig<F>,
     /// u16 lookup table,
@@ -51,43 +292,29 @@ pub struct TxCircuitTesterConfig<F: Field> {
     pub u16_table: U16Table,
 }
 
+impl<F: Field> TxCircuitTesterConfig<F> {
+    /// Return the config of the tx circuit
+    pub fn tx_config(&self) -> &TxCircuitConfig<F> {
+        &self.tx_config
+    }
+}
+
 impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
     type ConfigArgs = TxCircuitTesterConfigArgs<F>;
 
-    fn new(
-        meta: &mut ConstraintSystem<F>,
-        Self::ConfigArgs {
-            tx_table,
-            block_table,
-            keccak_table,
-            rlp_table,
-            sig_table,
-            u8_table,
-            u16_table,
-            challenges,
-        }: Self::ConfigArgs,
-    ) -> Self {
+    fn new(meta: &mut ConstraintSystem<F>, args: Self::ConfigArgs) -> Self {
+        let (u8_table, u16_table) = (args.u8_table, args.u16_table);
+        let extra_minimum_rows = args.extra_minimum_rows;
         let sig_config = SigCircuitConfig::new(
             meta,
             SigCircuitConfigArgs {
-                sig_table,
-                challenges: challenges.clone(),
-                keccak_table: keccak_table.clone(),
-            },
-        );
-        let tx_config = TxCircuitConfig::new(
-            meta,
-            TxCircuitConfigArgs {
-                sig_table,
-                block_table,
-                tx_table,
-                keccak_table,
-                rlp_table,
-                u8_table,
-                u16_table,
-                challenges,
+                sig_table: args.sig_table,
+                challenges: args.challenges.clone(),
+                keccak_table: args.keccak_table.clone(),
             },
         );
+        let mut tx_config = TxCircuitConfig::new(meta, args.tx_config_args());
+        tx_config.set_minimum_rows(tx_config.minimum_rows() + extra_minimum_rows);
         TxCircuitTesterConfig {
             tx_config,
             sig_config,
@@ -97,14 +324,35 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
     }
 }
 
+/// Options of the tx circuit in a [`TxCircuitTester`]. They are chosen by a type since
+/// `Circuit::configure` has no access to the circuit.
+pub trait TxCircuitTesterOptions: Clone + Debug + Default {
+    /// Set the options in `args`, which has the options of the super circuit.
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        args
+    }
+}
+
+/// The options of the tx circuit in the super circuit
+#[derive(Clone, Debug, Default)]
+pub struct SuperCircuitOptions;
+
+impl TxCircuitTesterOptions for SuperCircuitOptions {}
+
 /// The difference of this tester circuit and TxCircuit is that sig_circuit is included here.
 #[derive(Clone, Debug, Default)]
-pub struct TxCircuitTester<F: Field> {
+pub struct TxCircuitTester<F: Field, O: TxCircuitTesterOptions = SuperCircuitOptions> {
     pub(super) sig_circuit: SigCircuit<F>,
     pub(super) tx_circuit: TxCircuit<F>,
+    /// Challenges to assign the circuit with instead of the ones from the layouter
+    pub(super) challenges: Option<Challenges<Value<F>>>,
+    /// Whether the RLP table only holds the rows expected by the tx circuit instead of
+    /// all rows of the RLP state machine
+    pub(super) expected_rlp_rows_only: bool,
+    pub(super) _options: PhantomData<O>,
 }
 
-impl<F: Field> TxCircuitTester<F> {
+impl<F: Field, O: TxCircuitTesterOptions> TxCircuitTester<F, O> {
     /// Return a new TxCircuit
     pub fn new(
         max_txs: usize,
@@ -113,18 +361,34 @@ impl<F: Field> TxCircuitTester<F> {
         start_l1_queue_index: u64,
         txs: Vec<Transaction>,
     ) -> Self {
-        TxCircuitTester::<F> {
+        Self::from_tx_circuit(TxCircuit::new(
+            max_txs,
+            max_calldata,
+            chain_id,
+            start_l1_queue_index,
+            txs,
+        ))
+    }
+
+    /// Return a new TxCircuitTester verifying the signatures of `tx_circuit`
+    pub fn from_tx_circuit(tx_circuit: TxCircuit<F>) -> Self {
+        // one signature per tx slot
+        let signatures = tx_circuit.sig_table_inputs().unwrap();
+        TxCircuitTester {
             sig_circuit: SigCircuit {
-                max_verif: max_txs,
-                signatures: get_sign_data(&txs, max_txs, chain_id as usize).unwrap(),
+                max_verif: signatures.len(),
+                signatures,
                 _marker: PhantomData,
             },
-            tx_circuit: TxCircuit::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs),
+            tx_circuit,
+            challenges: None,
+            expected_rlp_rows_only: false,
+            _options: PhantomData,
         }
     }
 }
 
-impl<F: Field> SubCircuit<F> for TxCircuitTester<F> {
+impl<F: Field, O: TxCircuitTesterOptions> SubCircuit<F> for TxCircuitTester<F, O> {
     type Config = TxCircuitTesterConfig<F>;
 
     fn new_from_block(block: &crate::witness::Block<F>) -> Self {
@@ -139,7 +403,7 @@ impl<F: Field> SubCircuit<F> for TxCircuitTester<F> {
     fn synthesize_sub(
         &self,
         _config: &Self::Config,
-        _challenges: &Challenges<halo2_proofs::circuit::Value<F>>,
+        _challenges: &Challenges<Value<F>>,
         _layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         unimplemented!("not needed")
@@ -152,7 +416,7 @@ impl<F: Field> SubCircuit<F> for TxCircuitTester<F> {
 }
 
 // SigCircuit is embedded inside TxCircuitTester to make testing easier
-impl<F: Field> Circuit<F> for TxCircuitTester<F> {
+impl<F: Field, O: TxCircuitTesterOptions> Circuit<F> for TxCircuitTester<F, O> {
     type Config = (TxCircuitTesterConfig<F>, Challenges);
     type FloorPlanner = SimpleFloorPlanner;
     #[cfg(feature = "circuit-params")]
@@ -163,45 +427,8 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let block_table = BlockTable::construct(meta);
-        let tx_table = TxTable::construct(meta);
-        let keccak_table = KeccakTable::construct(meta);
-        let rlp_table = RlpTable::construct(meta);
-        let sig_table = SigTable::construct(meta);
-        let u8_table = U8Table::construct(meta);
-        let u16_table = U16Table::construct(meta);
-        let challenges = Challenges::construct(meta);
-
-        let config = {
-            let challenges = challenges.exprs(meta);
-            let sig_config = SigCircuitConfig::new(
-                meta,
-                SigCircuitConfigArgs {
-                    sig_table,
-                    challenges: challenges.clone(),
-                    keccak_table: keccak_table.clone(),
-                },
-            );
-            let tx_config = TxCircuitConfig::new(
-                meta,
-                TxCircuitConfigArgs {
-                    sig_table,
-                    block_table,
-                    tx_table,
-                    keccak_table,
-                    rlp_table,
-                    u8_table,
-                    u16_table,
-                    challenges,
-                },
-            );
-            TxCircuitTesterConfig {
-                tx_config,
-                sig_config,
-                u8_table,
-                u16_table,
-            }
-        };
+        let (args, challenges) = TxCircuitTesterConfigArgs::construct(meta);
+        let config = TxCircuitTesterConfig::new(meta, O::config_args(args));
 
         (config, challenges)
     }
@@ -211,40 +438,129 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
         (config, challenges): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        let challenges = challenges.values(&layouter);
-        config.u8_table.load(&mut layouter)?;
-        config.u16_table.load(&mut layouter)?;
-
-        let padding_txs = (self.tx_circuit.txs.len()..self.tx_circuit.max_txs)
-            .map(|i| {
-                let mut tx = Transaction::dummy(self.tx_circuit.chain_id);
-                tx.id = i + 1;
-                tx
-            })
-            .collect::<Vec<Transaction>>();
+        let challenges = self
+            .challenges
+            .unwrap_or_else(|| challenges.values(&layouter));
+        let range_table_rows = RangeTableRows {
+            u8_rows: config.u8_table.load(&mut layouter)?,
+            u16_rows: config.u16_table.load(&mut layouter)?,
+        };
+
+        let padding_txs = get_padding_txs(
+            self.tx_circuit.txs.len(),
+            self.tx_circuit.max_txs,
+            self.tx_circuit.chain_id,
+        );
 
-        config.tx_config.keccak_table.dev_load(
+        let keccak_inputs = self.tx_circuit.keccak_inputs_with_outputs()?;
+        config.tx_config.keccak_table.dev_load_with_outputs(
             &mut layouter,
-            &self.tx_circuit.keccak_inputs()?,
+            keccak_inputs
+                .iter()
+                .map(|(input, output)| (input.as_slice(), output.as_slice())),
             &challenges,
         )?;
-        config.tx_config.rlp_table.dev_load(
-            &mut layouter,
+        if self.expected_rlp_rows_only {
+            config.tx_config.rlp_table.dev_load_rows(
+                &mut layouter,
+                &self.tx_circuit.expected_rlp_rows(&challenges),
+            )?;
+        } else {
+            config.tx_config.rlp_table.dev_load(
+                &mut layouter,
+                self.tx_circuit
+                    .txs
+                    .iter()
+                    .chain(padding_txs.iter())
+                    .cloned()
+                    .collect(),
+                &challenges,
+            )?;
+        }
+
+        // the block table is left empty if the tx circuit doesn't look it up
+        if config.tx_config.enable_block_table_lookups {
             self.tx_circuit
-                .txs
-                .iter()
-                .chain(padding_txs.iter())
-                .cloned()
-                .collect(),
+                .assign_dev_block_table(config.tx_config.clone(), &mut layouter)?;
+        }
+        self.tx_circuit.synthesize_with_challenges(
+            &config.tx_config,
             &challenges,
+            Some(range_table_rows),
+            &mut layouter,
         )?;
-
-        self.tx_circuit
-            .assign_dev_block_table(config.tx_config.clone(), &mut layouter)?;
-        self.tx_circuit
-            .synthesize_sub(&config.tx_config, &challenges, &mut layouter)?;
         self.sig_circuit
             .synthesize_sub(&config.sig_config, &challenges, &mut layouter)?;
         Ok(())
     }
 }
+
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum AdviceColumn {
+    TxId,
+    TxIdInv,
+    TxValue,
+    TxIndex,
+    NumTxs,
+    NumAllTxsAcc,
+    NumL2Txs,
+    TxType,
+    RlpTag,
+    IsNone,
+    TxValueLength,
+    TxValueRlc,
+    IsCalldata,
+    IsFinal,
+    SectionRlc,
+    BlockNum,
+    ValueIsZeroInv,
+    TxAccessListCondition,
+    RlpHashTagCondition,
+    TxCalldataCondition,
+}
+
+impl AdviceColumn {
+    pub fn value<F: Field>(&self, config: &TxCircuitConfig<F>) -> Column<Advice> {
+        match self {
+            Self::TxId => config.tx_table.tx_id,
+            Self::TxIdInv => config.tx_id_is_zero.value_inv(),
+            Self::TxValue => config.tx_table.value,
+            Self::TxIndex => config.tx_table.index,
+            Self::NumTxs => config.num_txs,
+            Self::NumAllTxsAcc => config.num_all_txs_acc,
+            Self::NumL2Txs => config.num_l2_txs,
+            Self::TxType => config.tx_type,
+            Self::RlpTag => config.rlp_tag,
+            Self::IsNone => config.is_none,
+            Self::TxValueLength => config.tx_value_length,
+            Self::TxValueRlc => config.tx_value_rlc,
+            Self::IsCalldata => config.is_calldata,
+            Self::IsFinal => config.is_final,
+            Self::SectionRlc => config.section_rlc,
+            Self::BlockNum => config.block_num,
+            Self::ValueIsZeroInv => config.value_is_zero.value_inv(),
+            Self::TxAccessListCondition => {
+                config.lookup_conditions[&super::LookupCondition::TxAccessList]
+            }
+            Self::RlpHashTagCondition => {
+                config.lookup_conditions[&super::LookupCondition::RlpHashTag]
+            }
+            Self::TxCalldataCondition => {
+                config.lookup_conditions[&super::LookupCondition::TxCalldata]
+            }
+        }
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum FixedColumn {
+    Tag,
+}
+
+impl FixedColumn {
+    pub fn value<F: Field>(&self, config: &TxCircuitConfig<F>) -> Column<Fixed> {
+        match self {
+            Self::Tag => config.tx_table.tag,
+        }
+    }
+}
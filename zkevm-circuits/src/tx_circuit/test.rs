@@ -14,18 +14,30 @@ use std::cmp::max;
 
 use super::*;
 use crate::{
+    evm_circuit::util::rlc,
     sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
-    tx_circuit::{dev::TxCircuitTester, get_sign_data},
+    tx_circuit::{
+        dev::{
+            AdviceColumn, FixedColumn, TxCircuitTester, TxCircuitTesterConfig,
+            TxCircuitTesterConfigArgs, TxCircuitTesterOptions,
+        },
+        get_sign_data,
+    },
     util::{log2_ceil, unusable_rows},
+    witness::{Block, RecoveryKey, RlpFsmWitnessGen},
 };
+use bus_mapping::circuit_input_builder::CircuitsParams;
 use eth_types::{
     address,
     evm_types::gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
-    word, H256, U256, U64,
+    word, ToLittleEndian, H256, U256, U64,
 };
 use halo2_proofs::{
-    dev::{MockProver, VerifyFailure},
+    arithmetic::Field as Halo2Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::{unwrap_value, CellValue, FailureLocation, MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
+    plonk::Circuit,
 };
 use mock::{AddrOrWallet, MockTransaction};
 #[test]
@@ -36,6 +48,79 @@ fn tx_circuit_unusable_rows() {
     )
 }
 
+#[test]
+fn tx_circuit_degree() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    assert_eq!(config.tx_config.max_degree(), meta.degree());
+    assert!(config.tx_config.max_degree() <= 9);
+}
+
+#[test]
+fn tx_circuit_lookup_report() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let names = config
+        .tx_config
+        .lookup_report()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>();
+
+    for name in [
+        "tx call data gas cost in TxTable",
+        "lookup tx tag in RLP Table for signing",
+        "lookup tx tag in RLP Table for hashing",
+        "Sig table lookup",
+        "Keccak table lookup for TxSign and TxHash",
+    ] {
+        assert!(names.contains(&name), "{name} is missing in lookup report");
+    }
+}
+
+#[test]
+fn tx_circuit_describe() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let description = config.tx_config.describe();
+
+    let gate_names = description
+        .gates
+        .iter()
+        .map(|gate| gate.name.as_str())
+        .collect::<Vec<_>>();
+    for name in [
+        "is_padding_tx",
+        "tx call data init",
+        "tx call data bytes",
+        "tx access list",
+        "caller address == sv_address if it's not zero and tx_type != L1Msg",
+    ] {
+        assert!(
+            gate_names.contains(&name),
+            "{name} is missing in description"
+        );
+    }
+    assert!(description
+        .gates
+        .iter()
+        .all(|gate| !gate.constraints.is_empty() && !gate.columns.is_empty()));
+
+    // the lookups in the lookup report are described with their activation condition
+    for (name, condition) in config.tx_config.lookup_report() {
+        let lookup = description
+            .lookups
+            .iter()
+            .find(|lookup| lookup.name == *name)
+            .unwrap_or_else(|| panic!("{name} is missing in description"));
+        assert_eq!(lookup.condition.as_deref(), Some(*condition));
+        assert!(!lookup.table_columns.is_empty());
+    }
+
+    let json = serde_json::to_string(&description).unwrap();
+    assert!(json.contains("\"Sig table lookup\""));
+}
+
 #[cfg(test)]
 fn build_pre_eip155_tx() -> Transaction {
     // pre-eip155 tx downloaded from [etherscan](https://etherscan.io/getRawTx?tx=0x9cd2288e69623b109e25edc46bc518156498b521e5c162d96e1ab392ff1d9dff)
@@ -167,23 +252,193 @@ fn run<F: Field>(
     max_calldata: usize,
     start_l1_queue_index: u64,
 ) -> Result<(), Vec<VerifyFailure>> {
+    run_with_overrides::<F>(
+        txs,
+        chain_id,
+        max_txs,
+        max_calldata,
+        start_l1_queue_index,
+        HashMap::new(),
+    )
+}
+
+fn run_with_overrides<F: Field>(
+    txs: Vec<Transaction>,
+    chain_id: u64,
+    max_txs: usize,
+    max_calldata: usize,
+    start_l1_queue_index: u64,
+    overrides: HashMap<(AdviceColumn, usize), F>,
+) -> Result<(), Vec<VerifyFailure>> {
+    prove_with_overrides(
+        txs,
+        chain_id,
+        max_txs,
+        max_calldata,
+        start_l1_queue_index,
+        overrides,
+    )
+    .0
+}
+
+/// Like [`run_with_overrides`], but describes each failure together with the tx id
+/// and tag of the tx table row it happens at.
+fn run_with_failure_context<F: Field>(
+    txs: Vec<Transaction>,
+    chain_id: u64,
+    max_txs: usize,
+    max_calldata: usize,
+    start_l1_queue_index: u64,
+    overrides: HashMap<(AdviceColumn, usize), F>,
+) -> Result<(), Vec<String>> {
+    let (result, log) = prove_with_overrides(
+        txs,
+        chain_id,
+        max_txs,
+        max_calldata,
+        start_l1_queue_index,
+        overrides,
+    );
+    result.map_err(|failures| log.annotate_failures(&failures))
+}
+
+fn prove_with_overrides<F: Field>(
+    txs: Vec<Transaction>,
+    chain_id: u64,
+    max_txs: usize,
+    max_calldata: usize,
+    start_l1_queue_index: u64,
+    overrides: HashMap<(AdviceColumn, usize), F>,
+) -> (Result<(), Vec<VerifyFailure>>, TxAssignmentLog<F>) {
     let active_row_num = TxCircuit::<F>::min_num_rows(max_txs, max_calldata);
 
     let k = max(20, log2_ceil(active_row_num));
-    let circuit = TxCircuitTester::<F> {
-        sig_circuit: SigCircuit {
-            max_verif: max_txs,
-            signatures: get_sign_data(&txs, max_txs, chain_id as usize).unwrap(),
-            _marker: PhantomData,
-        },
-        tx_circuit: TxCircuit::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs),
+    let tx_circuit = TxCircuit {
+        overrides,
+        ..TxCircuit::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs)
     };
+    let circuit = TxCircuitTester::<F>::from_tx_circuit(tx_circuit);
     let prover = match MockProver::run(k, &circuit, vec![]) {
         Ok(prover) => prover,
         Err(e) => panic!("{e:#?}"),
     };
+    let log = circuit
+        .tx_circuit
+        .assignment_log()
+        .expect("log is recorded");
+
+    (
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        log,
+    )
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_sign_data_consistent_with_padding() {
+    const MAX_TXS: usize = 4;
+
+    let txs = vec![build_pre_eip155_tx(), build_l1_msg_tx()];
+    let chain_id = mock::MOCK_CHAIN_ID;
+    let circuit = TxCircuitTester::<Fr>::new(MAX_TXS, 0, chain_id, 0, txs.clone());
+
+    // the sign data fed to the sig circuit must be the same as the one assigned by
+    // `TxCircuit::synthesize_sub`
+    let padding_txs = get_padding_txs(txs.len(), MAX_TXS, chain_id);
+    assert_eq!(padding_txs.len(), MAX_TXS - txs.len());
+    assert_eq!(padding_txs[0].id, txs.len() + 1);
+    let sign_datas = txs_sign_data(
+        txs.iter().chain(padding_txs.iter()),
+        &TxHasher::default(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(circuit.sig_circuit.signatures.len(), MAX_TXS);
+    assert_eq!(
+        keccak_inputs_sign_verify(&circuit.sig_circuit.signatures),
+        keccak_inputs_sign_verify(&sign_datas)
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_sig_table_inputs() {
+    const MAX_TXS: usize = 4;
+
+    let txs = vec![
+        build_l1_msg_tx(),
+        build_pre_eip155_tx(),
+        build_eip1559_tx(3),
+    ];
+    let chain_id = mock::MOCK_CHAIN_ID;
+    let circuit = TxCircuitTester::<Fr>::new(MAX_TXS, 0, chain_id, 0, txs.clone());
+    let inputs = circuit.tx_circuit.sig_table_inputs().unwrap();
+
+    // one sig table row per tx slot, padding txs included
+    assert_eq!(inputs.len(), MAX_TXS);
+    assert_eq!(
+        keccak_inputs_sign_verify(&circuit.sig_circuit.signatures),
+        keccak_inputs_sign_verify(&inputs)
+    );
 
-    prover.verify_at_rows_par(0..active_row_num, 0..active_row_num)
+    let padding_txs = get_padding_txs(txs.len(), MAX_TXS, chain_id);
+    for (tx, sign_data) in txs.iter().chain(padding_txs.iter()).zip(inputs.iter()) {
+        if tx.tx_type.is_l1_msg() {
+            // the sig lookup is disabled for L1 msgs
+            assert!(sign_data.get_addr().is_zero());
+            continue;
+        }
+        // these are the values the tx circuit looks up in the sig table
+        let (r, s, v) = sign_data.signature;
+        assert_eq!(r.to_bytes(), tx.r.to_le_bytes());
+        assert_eq!(s.to_bytes(), tx.s.to_le_bytes());
+        assert_eq!(v, tx.tx_type.get_recovery_id(tx.v));
+        assert_eq!(sign_data.msg.to_vec(), tx.rlp_unsigned);
+        assert_eq!(sign_data.get_addr(), tx.caller_address);
+    }
+
+    assert_eq!(run::<Fr>(txs, chain_id, MAX_TXS, 0, 0), Ok(()));
+}
+
+#[test]
+fn tx_circuit_be_bytes_rlc_matches_value() {
+    let rand = Fr::from(0x10000);
+    let be_bytes_rlc = |bytes: &[u8]| unwrap_value(rlc_be_bytes(bytes, Value::known(rand)));
+
+    let nonce = 0x1234_u64;
+    assert!(be_bytes_rlc_matches_value(
+        Fr::from(nonce),
+        be_bytes_rlc(&nonce.to_be_bytes()),
+        rand
+    ));
+    // the bytes of a different number, e.g. in the wrong byte order
+    assert!(!be_bytes_rlc_matches_value(
+        Fr::from(nonce),
+        be_bytes_rlc(&nonce.to_le_bytes()),
+        rand
+    ));
+
+    let address = address!("0x1230000000000000000000000000000000000456");
+    assert!(be_bytes_rlc_matches_value(
+        address.to_scalar().unwrap(),
+        be_bytes_rlc(&address.to_fixed_bytes()),
+        rand
+    ));
+}
+
+#[test]
+fn tx_circuit_sign_data_error_reports_tx() {
+    let mut bad_tx = build_pre_eip155_tx();
+    // r is not a valid secp256k1 scalar
+    bad_tx.r = U256::MAX;
+    let txs = vec![build_l1_msg_tx(), bad_tx.clone()];
+
+    let err = get_sign_data(&txs, 4, mock::MOCK_CHAIN_ID, &TxHasher::default(), None)
+        .expect_err("signature is malformed");
+    assert_eq!(err.index, 1);
+    assert_eq!(err.hash, bad_tx.hash);
+    assert!(format!("{err}").contains("tx #1"));
 }
 
 #[test]
@@ -251,97 +506,2941 @@ fn tx_circuit_2tx_2max_tx() {
     );
 }
 
+/// The tx circuit without the block table lookups
+#[derive(Clone, Debug, Default)]
+struct WithoutBlockTable;
+
+impl TxCircuitTesterOptions for WithoutBlockTable {
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        TxCircuitTesterConfigArgs {
+            enable_block_table_lookups: false,
+            ..args
+        }
+    }
+}
+
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_0tx_1max_tx() {
-    const MAX_TXS: usize = 1;
-    const MAX_CALLDATA: usize = 80;
+fn tx_circuit_without_block_table_lookups() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let txs = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[3].clone(),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, tx)| {
+        let mut mock_tx = tx.clone();
+        mock_tx.transaction_idx((i + 1) as u64);
+        mock_tx.into()
+    })
+    .collect();
+    let circuit = TxCircuitTester::<Fr, WithoutBlockTable>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID,
+        0,
+        txs,
+    );
 
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    // the block table is not assigned at all
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
     assert_eq!(
-        run::<Fr>(vec![], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
         Ok(())
     );
 }
 
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_1tx_1max_tx() {
-    const MAX_TXS: usize = 1;
+fn tx_circuit_try_append() {
+    const MAX_TXS: usize = 2;
     const MAX_CALLDATA: usize = 32;
 
-    let tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+    let block_circuit = |tx: &MockTransaction, block_number: u64| {
+        let mut tx: Transaction = tx.clone().into();
+        tx.id = 1;
+        tx.block_number = block_number;
+        TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx])
+    };
 
+    // appending txs of a previous block fails
+    let mut tx_circuit = block_circuit(&mock::CORRECT_MOCK_TXS[1], 2);
+    assert!(tx_circuit
+        .try_append(block_circuit(&mock::CORRECT_MOCK_TXS[3], 1))
+        .is_err());
+    // appending txs that don't continue the l1 queue fails
+    let mut other = block_circuit(&mock::CORRECT_MOCK_TXS[3], 3);
+    other.start_l1_queue_index = 1;
+    assert!(tx_circuit.try_append(other).is_err());
+    assert_eq!(tx_circuit.txs.len(), 1);
+
+    let mut tx_circuit = block_circuit(&mock::CORRECT_MOCK_TXS[1], 1);
+    tx_circuit
+        .try_append(block_circuit(&mock::CORRECT_MOCK_TXS[3], 2))
+        .unwrap();
     assert_eq!(
-        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        tx_circuit.txs.iter().map(|tx| tx.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    // the budget of max_txs is used up
+    assert!(tx_circuit
+        .try_append(block_circuit(&mock::CORRECT_MOCK_TXS[3], 2))
+        .is_err());
+
+    let circuit = TxCircuitTester::<Fr>::from_tx_circuit(tx_circuit);
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
         Ok(())
     );
 }
 
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_1tx_2max_tx() {
-    const MAX_TXS: usize = 2;
-    const MAX_CALLDATA: usize = 320;
+fn tx_circuit_subset() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
 
-    let tx = build_pre_eip155_tx();
+    // three txs of the same block
+    let txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[1..4]
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| tx.clone().transaction_idx((i + 1) as u64).clone().into())
+        .collect();
+    let tx_circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs);
+    assert!(tx_circuit.subset(1..4).is_err());
 
+    // the last two txs of the block
+    let subset = tx_circuit.subset(1..3).unwrap();
     assert_eq!(
-        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        subset.txs.iter().map(|tx| tx.id).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(subset.txs[0].hash, tx_circuit.txs[1].hash);
+
+    let circuit = TxCircuitTester::<Fr>::from_tx_circuit(subset);
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
         Ok(())
     );
 }
 
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_l1_msg_tx() {
-    const MAX_TXS: usize = 4;
-    const MAX_CALLDATA: usize = 400;
+fn tx_circuit_calldata_init_only_at_first_byte() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
 
-    let tx = build_l1_msg_tx();
+    // both txs have calldata, so both have a calldata byte at index 0
+    let txs: Vec<Transaction> = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[2].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    let circuit =
+        TxCircuitTester::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs.clone());
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        Ok(())
+    );
+
+    // only the global first calldata byte is the init of the calldata region
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let calldata_first_row = MAX_TXS * TX_LEN + 1;
+    let q_calldata_first_rows: Vec<_> = prover.fixed()[config.tx_config.q_calldata_first.index()]
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| **cell == CellValue::Assigned(Fr::from(1)))
+        .map(|(row, _)| row)
+        .collect();
+    assert_eq!(q_calldata_first_rows, vec![calldata_first_row]);
+
+    // the init row must have index 0
+    assert!(run_with_overrides::<Fr>(
+        txs,
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0,
+        HashMap::from([((AdviceColumn::TxIndex, calldata_first_row), Fr::from(1))]),
+    )
+    .is_err());
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_assignment_record_replay() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let prove = |tx_circuit: TxCircuit<Fr>| {
+        let circuit = TxCircuitTester::<Fr>::from_tx_circuit(tx_circuit);
+        let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+        let k = max(20, log2_ceil(active_row_num));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let log = circuit
+            .tx_circuit
+            .assignment_log()
+            .expect("log is recorded");
+        (
+            prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+            log,
+        )
+    };
+
+    let txs = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[3].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    let (result, log) = prove(TxCircuit::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID,
+        0,
+        txs,
+    ));
+    assert_eq!(result, Ok(()));
+    // null row, then the fixed rows of each tx, then calldata
+    assert_eq!((log.rows[0].offset, log.rows[0].tag), (0, TxFieldTag::Null));
+    assert_eq!((log.rows[1].tx_id, log.rows[1].tag), (1, TxFieldTag::Nonce));
+    assert!(log.rows.windows(2).all(|w| w[0].offset < w[1].offset));
 
+    let (result, replayed_log) = prove(TxCircuit::from_replay(log.clone()));
+    assert_eq!(result, Ok(()));
+    assert_eq!(log.first_divergence(&replayed_log), None);
+
+    // a change in the witness is located at the first row it affects
+    let mut modified = log.clone();
+    modified.txs[0].gas += 1;
+    let (_, modified_log) = prove(TxCircuit::from_replay(modified));
+    let position = log.first_divergence(&modified_log).unwrap();
     assert_eq!(
-        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        (log.rows[position].tx_id, log.rows[position].tag),
+        (1, TxFieldTag::Gas)
+    );
+
+    // the replayed circuit hashes the txs with the hasher of the recorded one (the
+    // signatures don't match the alternate sighashes, so only the logs are compared)
+    let (_, alternate_log) = prove(TxCircuit {
+        hasher: TxHasher::new(DomainSeparatedKeccak),
+        ..TxCircuit::from_replay(log.clone())
+    });
+    let (_, replayed_log) = prove(TxCircuit::from_replay(alternate_log.clone()));
+    assert_eq!(alternate_log.first_divergence(&replayed_log), None);
+    let position = log.first_divergence(&alternate_log).unwrap();
+    assert_eq!(
+        (log.rows[position].tx_id, log.rows[position].tag),
+        (1, TxFieldTag::TxSignHash)
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_padding_region_is_null() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let txs: Vec<Transaction> = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[3].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    let padding_row = MAX_TXS * TX_LEN + 1 + MAX_CALLDATA;
+
+    let run = |overrides, fixed_overrides| {
+        let tx_circuit = TxCircuit {
+            overrides,
+            fixed_overrides,
+            ..TxCircuit::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs.clone())
+        };
+        let circuit = TxCircuitTester::<Fr>::from_tx_circuit(tx_circuit);
+        let k = max(20, log2_ceil(padding_row + 1));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.verify_at_rows_par(0..padding_row + 1, 0..padding_row + 1)
+    };
+
+    assert_eq!(run(HashMap::new(), HashMap::new()), Ok(()));
+    // a non-Null tag in the padding region
+    assert!(run(
+        HashMap::new(),
+        HashMap::from([(
+            (FixedColumn::Tag, padding_row),
+            Fr::from(TxFieldTag::CallData as u64)
+        )]),
+    )
+    .is_err());
+    // a tx table entry in the padding region
+    assert!(run(
+        HashMap::from([((AdviceColumn::TxValue, padding_row), Fr::from(1))]),
+        HashMap::new(),
+    )
+    .is_err());
+    // a lookup enabled in the padding region
+    assert_constraint_not_satisfied(
+        run(
+            HashMap::from([((AdviceColumn::TxCalldataCondition, padding_row), Fr::one())]),
+            HashMap::new(),
+        ),
+        "lookup condition == 0 in padding region",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_num_txs_disagrees_with_txs() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    // two txs in block 1 followed by a padding tx
+    let txs = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[3].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    // claim that block 1 has 3 txs on all rows of its txs
+    let overrides = (1..=2 * TX_LEN)
+        .map(|row| ((AdviceColumn::NumTxs, row), Fr::from(3)))
+        .collect();
+    let tx_circuit = TxCircuit {
+        overrides,
+        ..TxCircuit::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs)
+    };
+    // without the block table lookups the block table can't catch the wrong num_txs
+    let circuit = TxCircuitTester::<Fr, WithoutBlockTable>::from_tx_circuit(tx_circuit);
+
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    let errors = prover
+        .verify_at_rows_par(0..active_row_num, 0..active_row_num)
+        .expect_err("num_txs disagrees with the txs of the block");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if format!("{constraint}").contains("num_txs_acc == num_txs at the last tx of a block")
+        )),
+        "{errors:?}"
+    );
+}
+
+/// A hash table in another hashing domain than keccak256
+#[derive(Debug)]
+struct DomainSeparatedKeccak;
+
+impl TxHashFunction for DomainSeparatedKeccak {
+    fn hash(&self, input: &[u8]) -> [u8; 32] {
+        keccak256([b"l2-domain".as_slice(), input].concat())
+    }
+}
+
+/// Returns the mock tx 1 with id `id`, signed over its signing hash in the hashing
+/// domain of `hasher`
+fn sign_mock_tx_with_hasher(id: usize, hasher: &TxHasher) -> Transaction {
+    let mut mock_tx = mock::CORRECT_MOCK_TXS[1].clone();
+    mock_tx.transaction_idx(id as u64);
+    let rlp_unsigned = Transaction::from(mock_tx.clone()).rlp_unsigned;
+    let sig = mock_tx
+        .from
+        .as_wallet()
+        .sign_hash(H256(hasher.hash(&rlp_unsigned)))
+        .expect("sign mock tx");
+    // the recovery id of sig.v is 27 or 28, turn it into an eip155 v
+    mock_tx.sig_data((sig.v - 27 + 35 + 2 * mock_tx.chain_id, sig.r, sig.s));
+
+    let mut tx = Transaction::from(mock_tx);
+    tx.hash = H256(hasher.hash(&tx.rlp_signed));
+    tx
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_alternate_hash_table() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 512;
+
+    let l1_msg = build_l1_msg_tx();
+    let start_l1_queue_index = l1_msg.nonce;
+    let run = |hasher: TxHasher| {
+        // the signed tx is signed over its sighash in the hashing domain of the hasher,
+        // while the addresses of the sig circuit are still keccak256 hashes
+        let signed_tx = sign_mock_tx_with_hasher(2, &hasher);
+        let tx_circuit = TxCircuit {
+            hasher,
+            ..TxCircuit::new(
+                MAX_TXS,
+                MAX_CALLDATA,
+                mock::MOCK_CHAIN_ID,
+                start_l1_queue_index,
+                vec![l1_msg.clone(), signed_tx.clone()],
+            )
+        };
+        let hashes = (
+            tx_circuit.hasher.hash(&signed_tx.rlp_unsigned),
+            tx_circuit.hasher.hash(&signed_tx.rlp_signed),
+        );
+        let signatures = tx_circuit.sig_table_inputs().unwrap();
+        assert_eq!(signatures[1].get_addr(), signed_tx.caller_address);
+
+        let circuit = TxCircuitTester::<Fr>::from_tx_circuit(tx_circuit);
+        let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+        let k = max(20, log2_ceil(active_row_num));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        (
+            hashes,
+            prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        )
+    };
+
+    let ((keccak_sign_hash, keccak_hash), result) = run(TxHasher::default());
+    assert_eq!(result, Ok(()));
+    let keccak_tx = sign_mock_tx_with_hasher(2, &TxHasher::default());
+    assert_eq!(keccak_sign_hash, keccak256(&keccak_tx.rlp_unsigned));
+    assert_eq!(keccak_hash, keccak256(&keccak_tx.rlp_signed));
+
+    let ((alternate_sign_hash, alternate_hash), result) = run(TxHasher::new(DomainSeparatedKeccak));
+    assert_eq!(result, Ok(()));
+    assert_ne!(alternate_sign_hash, keccak_sign_hash);
+    assert_ne!(alternate_hash, keccak_hash);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_expected_rlp_rows() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 3600;
+
+    // l1 msg, eip1559 tx with an access list and an eip155 padding tx
+    let l1_msg = build_l1_msg_tx();
+    let txs = vec![l1_msg.clone(), build_eip1559_tx(2)];
+    let tx_circuit_tester = TxCircuitTester::<Fr>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID,
+        l1_msg.nonce,
+        txs.clone(),
+    );
+    let tx_circuit = &tx_circuit_tester.tx_circuit;
+
+    // the expected rows are produced by the RLP state machine
+    let key = |row: &RlpTableRow<Fr>| {
+        (
+            row.tx_id,
+            usize::from(row.format),
+            usize::from(row.rlp_tag),
+            unwrap_value(row.tag_value),
+            unwrap_value(row.tag_bytes_rlc),
+            row.tag_length,
+            row.is_output,
+            row.is_none,
+            row.access_list_idx,
+            row.storage_key_idx,
+        )
+    };
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x10000)),
+        Value::known(Fr::from(0x1000000)),
+    );
+    let padding_txs = get_padding_txs(txs.len(), MAX_TXS, mock::MOCK_CHAIN_ID);
+    let rlp_rows = txs
+        .iter()
+        .chain(padding_txs.iter())
+        .flat_map(|tx| tx.gen_sm_witness(&challenges))
+        .filter(|row| row.rlp_table.is_output)
+        .map(|row| key(&row.rlp_table))
+        .collect::<Vec<_>>();
+    let expected_rows = tx_circuit.expected_rlp_rows(&challenges);
+    assert!(expected_rows
+        .iter()
+        .any(|row| row.rlp_tag == Tag::AccessListStorageKey.into()));
+    for row in expected_rows.iter() {
+        assert!(rlp_rows.contains(&key(row)), "unexpected RLP row {row:?}");
+    }
+
+    // the lookups of the tx circuit only need the expected rows
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let circuit = TxCircuitTester {
+        expected_rlp_rows_only: true,
+        ..tx_circuit_tester
+    };
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
         Ok(())
     );
 }
 
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_bad_address() {
+fn tx_circuit_sig_r_is_zero() {
     const MAX_TXS: usize = 1;
-    const MAX_CALLDATA: usize = 32;
+    const MAX_CALLDATA: usize = 320;
 
-    let mut tx = mock::CORRECT_MOCK_TXS[0].clone();
-    // This address doesn't correspond to the account that signed this tx.
-    tx.from = AddrOrWallet::from(address!("0x1230000000000000000000000000000000000456"));
+    // a signature with r == 0 has no sign data for the sig circuit
+    let mut tx = build_pre_eip155_tx();
+    tx.r = U256::zero();
+    let tx_circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx]);
+    let err = tx_circuit.sig_table_inputs().unwrap_err();
+    assert_eq!(err.index, 0);
 
-    assert!(run::<Fr>(
-        vec![tx.into()],
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress, IsCreate, Value,
+    // CallDataRLC, CallDataLength, CallDataGasCost, TxDataGasCost, ChainID, SigV
+    let sig_r_row = 1 + 13;
+    // the sig table only holds r of the verified signature, so r == 0 can't be looked up
+    let overrides = HashMap::from([((AdviceColumn::TxValue, sig_r_row), Fr::zero())]);
+    let errors = run_with_overrides::<Fr>(
+        vec![build_pre_eip155_tx()],
         mock::MOCK_CHAIN_ID,
         MAX_TXS,
         MAX_CALLDATA,
-        0
+        0,
+        overrides,
     )
-    .is_err(),);
+    .expect_err("r == 0 is accepted");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "Sig table lookup"
+        )),
+        "Sig table lookup is not violated: {errors:?}"
+    );
+}
+
+/// Tx circuit with its lookup tables but without the sig circuit, for txs which don't
+/// look up the sig table (i.e. L1 msgs only and no padding txs)
+#[derive(Clone, Debug, Default)]
+struct TxCircuitWithoutSigCircuit(TxCircuit<Fr>);
+
+impl Circuit<Fr> for TxCircuitWithoutSigCircuit {
+    type Config = (TxCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let (args, challenges) = TxCircuitTesterConfigArgs::construct(meta);
+        let config = TxCircuitConfig::new(meta, args.tx_config_args());
+
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        let tx_circuit = &self.0;
+        let range_table_rows = RangeTableRows {
+            u8_rows: config.u8_table.load(&mut layouter)?,
+            u16_rows: config.u16_table.load(&mut layouter)?,
+        };
+        config
+            .keccak_table
+            .dev_load(&mut layouter, &tx_circuit.keccak_inputs()?, &challenges)?;
+        config
+            .rlp_table
+            .dev_load(&mut layouter, tx_circuit.txs.clone(), &challenges)?;
+        tx_circuit.assign_dev_block_table(config.clone(), &mut layouter)?;
+        tx_circuit.synthesize_with_challenges(
+            &config,
+            &challenges,
+            Some(range_table_rows),
+            &mut layouter,
+        )
+    }
 }
 
 #[test]
 #[cfg(feature = "scroll")]
-fn tx_circuit_to_is_zero() {
+fn tx_circuit_recommended_k() {
     const MAX_TXS: usize = 1;
-    const MAX_CALLDATA: usize = 32;
 
-    let mut tx = mock::CORRECT_MOCK_TXS[5].clone();
-    tx.transaction_index = U64::from(1);
+    let l1_msg = build_l1_msg_tx();
+    let tx_circuit = |max_calldata| {
+        TxCircuit::<Fr>::new(
+            MAX_TXS,
+            max_calldata,
+            mock::MOCK_CHAIN_ID,
+            l1_msg.nonce,
+            vec![l1_msg.clone()],
+        )
+    };
+
+    let (config, _) = TxCircuitWithoutSigCircuit::configure(&mut ConstraintSystem::default());
 
+    // small tx circuits are bounded by the u16 table
     assert_eq!(
-        run::<Fr>(
-            vec![tx.into()],
+        tx_circuit(l1_msg.call_data.len()).recommended_k(&config),
+        17
+    );
+
+    // the calldata region doesn't fit in 2^17 rows
+    let circuit = TxCircuitWithoutSigCircuit(tx_circuit(1 << 17));
+    let k = circuit.0.recommended_k(&config);
+    assert_eq!(k, 18);
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify_par(), Ok(()));
+    assert!(MockProver::run(k - 1, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn tx_circuit_recommended_k_with_more_minimum_rows() {
+    const MAX_TXS: usize = 1;
+    // null row + fixed rows of the tx + calldata region + padding row fill 2^17 rows
+    // but for the last 32
+    const MAX_CALLDATA: usize = (1 << 17) - TX_LEN - 2 - 32;
+
+    let block = Block::<Fr> {
+        chain_id: mock::MOCK_CHAIN_ID,
+        circuits_params: CircuitsParams {
+            max_txs: MAX_TXS,
+            max_calldata: MAX_CALLDATA,
+            max_rlp_rows: 2 * MAX_CALLDATA,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let tx_circuit = TxCircuit::<Fr>::new_from_block(&block);
+    let (_, block_rows) = TxCircuit::<Fr>::min_num_rows_block(&block);
+
+    for (extra_minimum_rows, expected_k) in [(0, 17), (64, 18)] {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (args, _) = TxCircuitTesterConfigArgs::construct(&mut meta);
+        let config = TxCircuitTesterConfig::new(
+            &mut meta,
+            TxCircuitTesterConfigArgs {
+                extra_minimum_rows,
+                ..args
+            },
+        )
+        .tx_config;
+        assert_eq!(
+            config.minimum_rows(),
+            meta.minimum_rows() + extra_minimum_rows
+        );
+
+        // the rows of the block, the null and padding rows and the reserved rows fit
+        // in 2^k rows but not in 2^(k - 1) rows
+        let k = tx_circuit.recommended_k(&config);
+        assert_eq!(k, expected_k);
+        let rows = block_rows + 2 + config.minimum_rows();
+        assert!(rows <= 1 << k && rows > 1 << (k - 1));
+    }
+}
+
+fn assert_leading_row_transition(txs: Vec<Transaction>) {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let run = |overrides| {
+        run_with_overrides::<Fr>(
+            txs.clone(),
             mock::MOCK_CHAIN_ID,
             MAX_TXS,
             MAX_CALLDATA,
-            0
-        ),
-        Ok(())
+            0,
+            overrides,
+        )
+    };
+
+    assert_eq!(run(HashMap::new()), Ok(()));
+    // the leading row pretends to belong to the 1st tx
+    let overrides = HashMap::from([((AdviceColumn::TxId, 0), Fr::one())]);
+    assert_constraint_not_satisfied(run(overrides.clone()), "tx_id == 0 in the leading row");
+    assert_constraint_not_satisfied(run(overrides), "tx_id changes after the leading row");
+    // the leading row is followed by a row with tx_id = 0
+    assert_constraint_not_satisfied(
+        run(HashMap::from([((AdviceColumn::TxId, 1), Fr::zero())])),
+        "tx_id == 1",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_leading_row_with_txs() {
+    assert_leading_row_transition(vec![build_pre_eip155_tx()]);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_leading_row_all_padding() {
+    assert_leading_row_transition(vec![]);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_sign_hashes() {
+    const MAX_TXS: usize = 2;
+
+    let tx = build_pre_eip155_tx();
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, 0, mock::MOCK_CHAIN_ID, 0, vec![tx.clone()]);
+    let sign_hashes = circuit.sign_hashes().unwrap();
+    // the padding tx has no sighash
+    assert_eq!(sign_hashes.len(), 1);
+
+    // the sighash of the mainnet tx
+    // 0x9cd2288e69623b109e25edc46bc518156498b521e5c162d96e1ab392ff1d9dff
+    assert_eq!(
+        hex::encode(sign_hashes[0]),
+        "cdff4db04d4f09a465fef6f78f0ab701626f5dd7bde1c844a0ade55bd7c487cb"
+    );
+    // which recovers its sender
+    let sig = Signature {
+        r: tx.r,
+        s: tx.s,
+        v: tx.v,
+    };
+    assert_eq!(
+        sig.recover(H256(sign_hashes[0])).unwrap(),
+        address!("0xcf40d0d2b44f2b66e07cace1372ca42b73cf21a3")
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_sign_hashes_match_tx_sign_hash_rows() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+
+    let evm_word = Value::known(Fr::from(0x100003));
+    let circuit = TxCircuitTester::<Fr> {
+        challenges: Some(Challenges::mock(
+            Value::known(Fr::from(0x100001)),
+            Value::known(Fr::from(0x100002)),
+            evm_word,
+        )),
+        ..TxCircuitTester::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            mock::MOCK_CHAIN_ID,
+            0,
+            vec![build_pre_eip155_tx()],
+        )
+    };
+    let k = max(
+        20,
+        log2_ceil(TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA)),
+    );
+    MockProver::run(k, &circuit, vec![]).expect("MockProver::run shall not fail");
+
+    let sign_hashes = circuit.tx_circuit.sign_hashes().unwrap();
+    assert_eq!(sign_hashes.len(), 1);
+    let value_cells = circuit.tx_circuit.value_cells.borrow();
+    let value_cells = value_cells.as_ref().expect("tx value cells are exported");
+    for (i, sign_hash) in sign_hashes.iter().enumerate() {
+        let tx_sign_hash = value_cells[i * TX_LEN + TX_SIGN_HASH_OFFSET - 1]
+            .value()
+            .copied();
+        assert_eq!(
+            unwrap_value(tx_sign_hash),
+            unwrap_value(rlc_be_bytes(sign_hash, evm_word)),
+            "TxSignHash of tx {}",
+            i + 1
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_block_num_copied_to_all_rows() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_pre_eip155_tx();
+    let wrong_block_num = Fr::from(tx.block_number + 1);
+    let run = |overrides| {
+        run_with_overrides::<Fr>(
+            vec![tx.clone()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        )
+    };
+
+    assert_eq!(run(HashMap::new()), Ok(()));
+    // the Nonce row and a row in the middle of the tx are bound to the BlockNumber row,
+    // which is the last fixed row of the tx
+    for row in [1, TX_LEN / 2] {
+        assert_constraint_not_satisfied(
+            run(HashMap::from([(
+                (AdviceColumn::BlockNum, row),
+                wrong_block_num,
+            )])),
+            "block_num",
+        );
+    }
+    assert_constraint_not_satisfied(
+        run(HashMap::from([(
+            (AdviceColumn::BlockNum, TX_LEN),
+            wrong_block_num,
+        )])),
+        "block_num = tx_table.value if tag == BlockNum",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_fields_copied_across_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+
+    let run = |overrides| {
+        run_with_overrides::<Fr>(
+            vec![build_pre_eip155_tx()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        )
+    };
+
+    // num_txs and block_num vary in the middle of the 1st tx
+    for (column, name) in [
+        (AdviceColumn::NumTxs, "num_txs"),
+        (AdviceColumn::BlockNum, "block_num"),
+    ] {
+        assert_constraint_not_satisfied(
+            run(HashMap::from([((column, TX_LEN / 2), Fr::from(7))])),
+            name,
+        );
+    }
+}
+
+/// The tx circuit checking that the gas of each tx is at most `MAX_TX_GAS`
+#[derive(Clone, Debug, Default)]
+struct MaxTxGas<const MAX_TX_GAS: u64>;
+
+impl<const MAX_TX_GAS: u64> TxCircuitTesterOptions for MaxTxGas<MAX_TX_GAS> {
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        TxCircuitTesterConfigArgs {
+            max_tx_gas: Some(MAX_TX_GAS),
+            ..args
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_max_tx_gas() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+    // gas of the pre-eip155 tx
+    const GAS: u64 = 150_000;
+
+    let tx = build_pre_eip155_tx();
+    assert_eq!(tx.gas, GAS);
+    let tx_circuit = TxCircuit::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx]);
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+
+    let circuit = TxCircuitTester::<Fr, MaxTxGas<GAS>>::from_tx_circuit(tx_circuit.clone());
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        Ok(())
+    );
+
+    let circuit = TxCircuitTester::<Fr, MaxTxGas<{ GAS - 1 }>>::from_tx_circuit(tx_circuit);
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_constraint_not_satisfied(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        "gas < max_tx_gas + 1",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_calldata_over_u16_rows() {
+    const MAX_TXS: usize = 2;
+    const CALLDATA_PER_TX: usize = 33_000;
+    const MAX_CALLDATA: usize = MAX_TXS * CALLDATA_PER_TX;
+
+    // the calldata region spans more rows than the u16 table has entries
+    assert!(MAX_CALLDATA > 1 << 16);
+    let sender = mock::CORRECT_MOCK_TXS[0].from.clone();
+    let txs = (0..MAX_TXS)
+        .map(|i| {
+            let call_data = (0..CALLDATA_PER_TX)
+                .map(|j| (j % 2) as u8 * 0xff)
+                .collect::<Vec<_>>();
+            MockTransaction::default()
+                .transaction_idx((i + 1) as u64)
+                .from(sender.clone())
+                .to(mock::MOCK_ACCOUNTS[0])
+                .nonce(U256::from(i))
+                .input(call_data.into())
+                .build()
+                .into()
+        })
+        .collect();
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_calldata_init_padding_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx: Transaction = MockTransaction::default()
+        .transaction_idx(1)
+        .from(mock::CORRECT_MOCK_TXS[0].from.clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .input(vec![1, 2, 3].into())
+        .build()
+        .into();
+    let calldata_first_row = MAX_TXS * TX_LEN + 1;
+    let calldata_rows = calldata_first_row..calldata_first_row + tx.call_data.len();
+    assert_eq!(
+        run::<Fr>(
+            vec![tx.clone()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0
+        ),
+        Ok(())
+    );
+
+    // the calldata of the tx is attributed to the padding tx 2 instead
+    let padding_tx_id = Fr::from(2);
+    let overrides = calldata_rows
+        .flat_map(|row| {
+            [
+                ((AdviceColumn::TxId, row), padding_tx_id),
+                (
+                    (AdviceColumn::TxIdInv, row),
+                    padding_tx_id.invert().unwrap(),
+                ),
+            ]
+        })
+        .collect();
+    let errors = run_with_overrides::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0,
+        overrides,
+    )
+    .expect_err("calldata of a padding tx must be rejected");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "calldata init tx in TxTable"
+        )),
+        "{errors:#?}"
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_bad_call_data_gas_cost() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let run_tx =
+        |tx: Transaction| run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0);
+
+    // tx with call data
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    tx.call_data_gas_cost += 1;
+    let errors = run_tx(tx).expect_err("wrong call data gas cost is accepted");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "tx call data gas cost in TxTable"
+        )),
+        "{errors:?}"
+    );
+
+    // tx without call data
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[3]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    assert!(tx.call_data.is_empty());
+    tx.call_data_gas_cost = 4;
+    assert_constraint_not_satisfied(run_tx(tx), "CallDataGasCost.value == 0");
+}
+
+/// The tx circuit reserving extra rows at the end of the circuit
+#[derive(Clone, Debug, Default)]
+struct MoreMinimumRows;
+
+impl TxCircuitTesterOptions for MoreMinimumRows {
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        TxCircuitTesterConfigArgs {
+            extra_minimum_rows: 64,
+            ..args
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_more_minimum_rows() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+
+    let circuit = TxCircuitTester::<Fr, MoreMinimumRows>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID,
+        0,
+        vec![build_pre_eip155_tx()],
+    );
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_inconsistent_sig_v() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 3200;
+
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress, IsCreate, Value,
+    // CallDataRLC, CallDataLength, CallDataGasCost, TxDataGasCost, ChainID
+    let sig_v_row = 1 + 12;
+    let run_with_sig_v = |tx: Transaction, v: u64| {
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([((AdviceColumn::TxValue, sig_v_row), Fr::from(v))]),
+        )
+    };
+    let assert_sig_lookup_fails = |result: Result<(), Vec<VerifyFailure>>| {
+        let errors = result.expect_err("v inconsistent with the recovery id is accepted");
+        assert!(
+            errors.iter().any(|error| matches!(
+                error,
+                VerifyFailure::Lookup { name, .. } if name == "Sig table lookup"
+            )),
+            "Sig table lookup is not violated: {errors:?}"
+        );
+    };
+
+    // pre-eip155 tx: v Є {27, 28}
+    let tx = build_pre_eip155_tx();
+    assert_eq!(tx.v, 28);
+    assert_eq!(run_with_sig_v(tx.clone(), 28), Ok(()));
+    // the other allowed value has the wrong recovery id
+    assert_sig_lookup_fails(run_with_sig_v(tx.clone(), 27));
+    assert_constraint_not_satisfied(run_with_sig_v(tx, 29), "V - 27 Є {0, 1}");
+
+    // eip1559 tx: v Є {0, 1}
+    let tx = build_eip1559_tx(1);
+    assert!(tx.v <= 1);
+    assert_sig_lookup_fails(run_with_sig_v(tx.clone(), 1 - tx.v));
+    assert_constraint_not_satisfied(run_with_sig_v(tx, 2), "V Є {0, 1}");
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_recovery_cache() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    let recovery_cache = RecoveryCache::default();
+    let new_tx_circuit = |tx: Transaction| TxCircuit::<Fr> {
+        recovery_cache: Some(recovery_cache.clone()),
+        ..TxCircuit::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx])
+    };
+    let prove = |tx_circuit: TxCircuit<Fr>| {
+        let circuit = TxCircuitTester::<Fr>::from_tx_circuit(tx_circuit);
+        let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+        let k = max(20, log2_ceil(active_row_num));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num)
+    };
+
+    let tx = build_pre_eip155_tx();
+    let key = RecoveryKey {
+        msg_hash: keccak256(&tx.rlp_unsigned),
+        v: tx.tx_type.get_recovery_id(tx.v),
+        r: tx.r,
+        s: tx.s,
+    };
+
+    // the first proof recovers the sender and fills the cache
+    assert_eq!(prove(new_tx_circuit(tx.clone())), Ok(()));
+    let pk = recovery_cache.read().unwrap()[&key];
+    assert_eq!(
+        new_tx_circuit(tx.clone()).sig_table_inputs().unwrap()[0].pk,
+        pk
+    );
+    let cache_len = recovery_cache.read().unwrap().len();
+
+    // the second proof of the same tx doesn't add entries
+    assert_eq!(prove(new_tx_circuit(tx.clone())), Ok(()));
+    assert_eq!(recovery_cache.read().unwrap().len(), cache_len);
+
+    // and takes the public key from the cache instead of recovering it
+    let other_pk = Transaction::from(mock::CORRECT_MOCK_TXS[0].clone())
+        .sign_data()
+        .unwrap()
+        .pk;
+    assert_ne!(other_pk, pk);
+    recovery_cache.write().unwrap().insert(key, other_pk);
+    let sign_datas = new_tx_circuit(tx.clone()).sig_table_inputs().unwrap();
+    assert_eq!(sign_datas[0].pk, other_pk);
+    // a wrong cache entry is caught by the sig circuit
+    assert!(prove(new_tx_circuit(tx.clone())).is_err());
+
+    // the entry is only used for the same signature of the same sighash
+    let mut other_sig_tx = tx;
+    other_sig_tx.v = 27;
+    assert!(new_tx_circuit(other_sig_tx)
+        .sig_table_inputs()
+        .map_or(true, |sign_datas| sign_datas[0].pk != other_pk));
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_address_over_20_bytes() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    // null row + Nonce, GasPrice, Gas
+    let caller_address_row = 1 + 3;
+    let callee_address_row = caller_address_row + 1;
+    let tx = build_pre_eip155_tx();
+    let two_pow_160 = gadgets::util::pow_of_two::<Fr>(160);
+
+    for (row, address) in [
+        (caller_address_row, tx.caller_address),
+        (callee_address_row, tx.callee_address.unwrap_or_default()),
+    ] {
+        // the address plus 2^160 has the same low 20 bytes as the address
+        let value = address.to_scalar().unwrap() + two_pow_160;
+        assert_constraint_not_satisfied(
+            run_with_overrides::<Fr>(
+                vec![tx.clone()],
+                mock::MOCK_CHAIN_ID,
+                MAX_TXS,
+                MAX_CALLDATA,
+                0,
+                HashMap::from([((AdviceColumn::TxValue, row), value)]),
+            ),
+            "address == sum(limb_i * 2^(16 * i))",
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_0tx_1max_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 80;
+
+    assert_eq!(
+        run::<Fr>(vec![], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_1tx_1max_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0].clone().into();
+
+    assert_eq!(
+        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_1tx_2max_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 320;
+
+    let tx = build_pre_eip155_tx();
+
+    assert_eq!(
+        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_l1_msg_tx() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 400;
+
+    let tx = build_l1_msg_tx();
+
+    assert_eq!(
+        run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_num_l1_msgs_and_l2_txs() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 3600;
+
+    // l1 msg with queue index 11 followed by an l2 tx in the same block
+    let l1_msg = build_l1_msg_tx();
+    let txs = vec![l1_msg.clone(), build_eip1559_tx(2)];
+    // the l1 msg with queue index 10 is skipped
+    let start_l1_queue_index = l1_msg.nonce - 1;
+
+    let circuit = TxCircuitTester::<Fr>::new(
+        MAX_TXS,
+        MAX_CALLDATA,
+        mock::MOCK_CHAIN_ID,
+        start_l1_queue_index,
+        txs,
+    );
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(
+        prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        Ok(())
+    );
+
+    let num_txs_cells = circuit.tx_circuit.num_txs_cells.borrow();
+    let num_txs_cells = num_txs_cells.as_ref().expect("num txs cells are exported");
+    assert_eq!(num_txs_cells.len(), 1);
+    let [num_l1_msgs, num_l2_txs] = num_txs_cells[&l1_msg.block_number]
+        .clone()
+        .map(|cell| unwrap_value(cell.value().copied()));
+    assert_eq!(num_l1_msgs, Fr::from(2));
+    assert_eq!(num_l2_txs, Fr::from(1));
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_l1_msg_flags() {
+    const MAX_TXS: usize = 4;
+
+    let l1_msg = build_l1_msg_tx();
+    let mut pre_eip155_tx = build_pre_eip155_tx();
+    pre_eip155_tx.id = 3;
+    let txs = vec![l1_msg.clone(), build_eip1559_tx(2), pre_eip155_tx];
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, 0, mock::MOCK_CHAIN_ID, l1_msg.nonce, txs);
+    assert_eq!(circuit.l1_msg_flags(), vec![true, false, false]);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_intrinsic_gas() {
+    const MAX_TXS: usize = 4;
+
+    let tx_with_call_data = |id, call_data: Vec<u8>| {
+        let mut tx = build_pre_eip155_tx();
+        tx.id = id;
+        tx.call_data = call_data;
+        tx
+    };
+    let transfer = tx_with_call_data(1, vec![]);
+    let call = tx_with_call_data(2, vec![0, 1, 2]);
+    let mut create = tx_with_call_data(3, vec![0xff; 33]);
+    create.is_create = true;
+    create.callee_address = None;
+
+    let circuit = TxCircuit::<Fr>::new(
+        MAX_TXS,
+        0,
+        mock::MOCK_CHAIN_ID,
+        0,
+        vec![transfer, call, create],
+    );
+    let config = |calldata_gas_costs| {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (args, _) = TxCircuitTesterConfigArgs::construct(&mut meta);
+        TxCircuitTesterConfig::new(
+            &mut meta,
+            TxCircuitTesterConfigArgs {
+                calldata_gas_costs,
+                ..args
+            },
+        )
+        .tx_config
+    };
+    // 2 init code words * 2 after Shanghai
+    let init_code_gas = if cfg!(feature = "shanghai") { 4 } else { 0 };
+
+    // 21000, 21000 + 4 + 2 * 16, 53000 + 33 * 16
+    assert_eq!(
+        circuit.intrinsic_gas(&config(CalldataGasCosts::default())),
+        vec![21000, 21036, 53528 + init_code_gas]
+    );
+    // 21000, 21000 + 4 + 2 * 68, 53000 + 33 * 68
+    assert_eq!(
+        circuit.intrinsic_gas(&config(PRE_ISTANBUL_CALLDATA_GAS_COSTS)),
+        vec![21000, 21140, 55244 + init_code_gas]
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_keccak_capacity() {
+    use crate::keccak_circuit::{keccak_packed_multi::multi_keccak, KeccakCircuit};
+
+    let circuit = TxCircuit::<Fr>::new(2, 0, mock::MOCK_CHAIN_ID, 0, vec![build_pre_eip155_tx()]);
+    let keccak_rows = circuit.keccak_row_estimate().unwrap();
+    assert!(circuit.assert_keccak_capacity(keccak_rows).is_ok());
+    assert!(circuit.assert_keccak_capacity(keccak_rows - 1).is_err());
+
+    // the estimate is exactly what the keccak circuit needs to hash the inputs
+    let inputs = circuit.keccak_inputs().unwrap();
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x10000)),
+        Value::known(Fr::from(0x1000000)),
+    );
+    for (num_rows, fits) in [(keccak_rows, true), (keccak_rows - 1, false)] {
+        let capacity = KeccakCircuit::<Fr>::capacity_for_row(num_rows);
+        assert_eq!(multi_keccak(&inputs, challenges, capacity).is_ok(), fits);
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_is_padding() {
+    const MAX_TXS: usize = 4;
+
+    let mut zero_address_tx = build_eip1559_tx(2);
+    zero_address_tx.caller_address = Address::zero();
+    let txs = vec![build_pre_eip155_tx(), zero_address_tx];
+
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, 0, mock::MOCK_CHAIN_ID, 0, txs);
+    let is_padding = (0..MAX_TXS)
+        .map(|i| circuit.is_padding(i))
+        .collect::<Vec<_>>();
+    assert_eq!(is_padding, vec![false, true, true, true]);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_bad_address() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx = mock::CORRECT_MOCK_TXS[0].clone();
+    // This address doesn't correspond to the account that signed this tx.
+    tx.from = AddrOrWallet::from(address!("0x1230000000000000000000000000000000000456"));
+
+    assert!(run::<Fr>(
+        vec![tx.into()],
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0
+    )
+    .is_err(),);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_to_is_zero() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx = mock::CORRECT_MOCK_TXS[5].clone();
+    tx.transaction_index = U64::from(1);
+
+    assert_eq!(
+        run::<Fr>(
+            vec![tx.into()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0
+        ),
+        Ok(())
+    );
+}
+
+fn assert_constraint_not_satisfied(result: Result<(), Vec<VerifyFailure>>, name: &str) {
+    let errors = result.expect_err("result is not an error");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if format!("{constraint}").contains(name)
+        )),
+        "{name} is not violated: {errors:?}"
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_bad_is_none_value_length() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    // the value of the pre-eip155 tx is 0, so its Value row has is_none = 1.
+    let tx = build_pre_eip155_tx();
+    assert!(tx.value.is_zero());
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress, IsCreate
+    let value_row = 1 + 6;
+    let overrides = HashMap::from([((AdviceColumn::TxValueLength, value_row), Fr::one())]);
+
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        ),
+        "is_none is true => tx_value_length == 0",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_is_create_not_boolean() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_pre_eip155_tx();
+    assert!(!tx.is_create);
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress
+    let is_create_row = 1 + 5;
+
+    for (is_create, constraint) in [(2, "IsCreate is boolean"), (1, "is_create == is_none")] {
+        assert_constraint_not_satisfied(
+            run_with_overrides::<Fr>(
+                vec![tx.clone()],
+                mock::MOCK_CHAIN_ID,
+                MAX_TXS,
+                MAX_CALLDATA,
+                0,
+                HashMap::from([((AdviceColumn::TxValue, is_create_row), Fr::from(is_create))]),
+            ),
+            constraint,
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_rlp_null_tag_with_value() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress
+    let is_create_row = 1 + 5;
+
+    for (column, constraint) in [
+        (
+            AdviceColumn::TxValueRlc,
+            "rlp_tag == Null => tx_value_rlc == 0",
+        ),
+        (
+            AdviceColumn::TxValueLength,
+            "rlp_tag == Null => tx_value_length == 0",
+        ),
+    ] {
+        assert_constraint_not_satisfied(
+            run_with_overrides::<Fr>(
+                vec![build_pre_eip155_tx()],
+                mock::MOCK_CHAIN_ID,
+                MAX_TXS,
+                MAX_CALLDATA,
+                0,
+                HashMap::from([((column, is_create_row), Fr::from(0x1234))]),
+            ),
+            constraint,
+        );
+    }
+}
+
+#[cfg(feature = "scroll")]
+fn assigned_tx_values(challenges: Challenges<Value<Fr>>) -> Vec<Fr> {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    let circuit = TxCircuitTester::<Fr> {
+        challenges: Some(challenges),
+        ..TxCircuitTester::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            mock::MOCK_CHAIN_ID,
+            0,
+            vec![build_pre_eip155_tx()],
+        )
+    };
+    let k = max(
+        20,
+        log2_ceil(TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA)),
+    );
+    MockProver::run(k, &circuit, vec![]).expect("MockProver::run shall not fail");
+
+    let value_cells = circuit.tx_circuit.value_cells.borrow();
+    value_cells
+        .as_ref()
+        .expect("tx value cells are exported")
+        .iter()
+        .map(|cell| unwrap_value(cell.value().copied()))
+        .collect()
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_synthesize_with_fixed_challenges() {
+    let challenges = |evm_word: u64, keccak_input: u64| {
+        Challenges::mock(
+            Value::known(Fr::from(evm_word)),
+            Value::known(Fr::from(keccak_input)),
+            Value::known(Fr::from(0x100)),
+        )
+    };
+
+    let values = assigned_tx_values(challenges(0x1ab, 0x10000));
+    // the same challenges reproduce the exact same witness
+    assert_eq!(values, assigned_tx_values(challenges(0x1ab, 0x10000)));
+    // while the RLCs (e.g. CallDataRLC and TxSignRLC) change with the challenges
+    let other_values = assigned_tx_values(challenges(0x1ac, 0x10001));
+    assert_eq!(values.len(), other_values.len());
+    assert_ne!(values, other_values);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_calldata_region_boundary() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    // the first row of the calldata region is right after the fixed rows of all txs
+    let boundary_row = MAX_TXS * TX_LEN + 1;
+    let overrides = HashMap::from([((AdviceColumn::IsCalldata, boundary_row), Fr::zero())]);
+
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![build_pre_eip155_tx()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        ),
+        "q_calldata_first row is in the calldata region",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_hash_length_mismatch() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_pre_eip155_tx();
+    // null row + 18 fixed rows before TxHashLength
+    let hash_length_row = 1 + 18;
+    let bad_length = Fr::from(tx.rlp_signed.len() as u64 + 1);
+
+    let errors = run_with_overrides::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0,
+        HashMap::from([((AdviceColumn::TxValue, hash_length_row), bad_length)]),
+    )
+    .expect_err("keccak input_len differing from the RLP length must be rejected");
+    for lookup in [
+        "Keccak table lookup for TxSign and TxHash",
+        "lookup tx tag in RLP Table for hashing",
+    ] {
+        assert!(
+            errors.iter().any(|error| matches!(
+                error,
+                VerifyFailure::Lookup { name, .. } if name == lookup
+            )),
+            "{errors:#?}"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_padding_num_all_txs_acc() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    // the 1st padding tx (tx_id = 2) must have num_all_txs_acc = 1. Keep
+    // num_all_txs_acc = num_l1_msgs + num_l2_txs satisfied on all of its rows.
+    let padding_tx_rows = 1 + TX_LEN..1 + 2 * TX_LEN;
+    let overrides = padding_tx_rows
+        .flat_map(|row| {
+            [
+                ((AdviceColumn::NumAllTxsAcc, row), Fr::from(5)),
+                ((AdviceColumn::NumL2Txs, row), Fr::from(5)),
+            ]
+        })
+        .collect();
+
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![build_pre_eip155_tx()],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        ),
+        "num_all_txs_acc of padding txs increments by 1",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_concatenated_calldata() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+    // Nonce, GasPrice, Gas, CallerAddress, CalleeAddress, IsCreate, Value
+    const CALLDATA_RLC_IDX: usize = 7;
+
+    let sender = mock::CORRECT_MOCK_TXS[0].from.clone();
+    let txs: Vec<Transaction> = [vec![1, 2, 3], vec![], vec![0, 4, 0, 5]]
+        .into_iter()
+        .enumerate()
+        .map(|(i, call_data)| {
+            MockTransaction::default()
+                .transaction_idx((i + 1) as u64)
+                .from(sender.clone())
+                .to(mock::MOCK_ACCOUNTS[0])
+                .nonce(U256::from(i))
+                .input(call_data.into())
+                .build()
+                .into()
+        })
+        .collect();
+
+    let keccak_input = Fr::from(0x10000);
+    let circuit = TxCircuitTester::<Fr> {
+        challenges: Some(Challenges::mock(
+            Value::known(Fr::from(0x1ab)),
+            Value::known(keccak_input),
+            Value::known(Fr::from(0x100)),
+        )),
+        ..TxCircuitTester::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs.clone())
+    };
+    let calldata = circuit.tx_circuit.concatenated_calldata();
+    assert_eq!(calldata, vec![1, 2, 3, 0, 4, 0, 5]);
+
+    let k = max(
+        20,
+        log2_ceil(TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA)),
+    );
+    MockProver::run(k, &circuit, vec![]).expect("MockProver::run shall not fail");
+
+    // chain the assigned CallDataRLC of each tx into the RLC of the whole slice
+    let value_cells = circuit.tx_circuit.value_cells.borrow();
+    let value_cells = value_cells.as_ref().expect("tx value cells are exported");
+    let assigned_rlc = txs.iter().enumerate().fold(Fr::zero(), |acc, (i, tx)| {
+        let tx_rlc = unwrap_value(value_cells[i * TX_LEN + CALLDATA_RLC_IDX].value().copied());
+        acc * keccak_input.pow_vartime([tx.call_data.len() as u64]) + tx_rlc
+    });
+    let calldata_rlc = calldata.iter().fold(Fr::zero(), |acc, byte| {
+        acc * keccak_input + Fr::from(*byte as u64)
+    });
+    assert_eq!(assigned_rlc, calldata_rlc);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_is_calldata_in_fixed_region() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 320;
+
+    // the Nonce row and the last fixed row (BlockNum) right before the calldata region
+    let last_fixed_row = MAX_TXS * TX_LEN;
+    for (row, constraint) in [
+        (1, "is_calldata"),
+        (
+            last_fixed_row,
+            "row before q_calldata_first is in the fixed region",
+        ),
+    ] {
+        assert_constraint_not_satisfied(
+            run_with_overrides::<Fr>(
+                vec![build_pre_eip155_tx()],
+                mock::MOCK_CHAIN_ID,
+                MAX_TXS,
+                MAX_CALLDATA,
+                0,
+                HashMap::from([((AdviceColumn::IsCalldata, row), Fr::one())]),
+            ),
+            constraint,
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_effective_gas_prices() {
+    let legacy_tx = build_pre_eip155_tx();
+    let eip1559_tx = build_eip1559_tx(2);
+    let max_fee = eip1559_tx.max_fee_per_gas.as_u64();
+    let max_priority_fee = eip1559_tx.max_priority_fee_per_gas.as_u64();
+    assert!(max_priority_fee < max_fee);
+    let circuit = TxCircuit::<Fr>::new(
+        2,
+        0,
+        mock::MOCK_CHAIN_ID,
+        0,
+        vec![legacy_tx.clone(), eip1559_tx],
+    );
+
+    // the priority fee is paid in full under a low base fee
+    let base_fee = max_fee - max_priority_fee - 1;
+    assert_eq!(
+        circuit.effective_gas_prices(base_fee),
+        vec![legacy_tx.gas_price.as_u64(), base_fee + max_priority_fee]
+    );
+    // and capped by max_fee_per_gas under a high one
+    assert_eq!(
+        circuit.effective_gas_prices(max_fee),
+        vec![legacy_tx.gas_price.as_u64(), max_fee]
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_l1_msg_nonzero_chain_id() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 400;
+
+    let l1_msg = build_l1_msg_tx();
+    let start_l1_queue_index = l1_msg.nonce;
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![l1_msg],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            start_l1_queue_index,
+            HashMap::from([(
+                (AdviceColumn::TxValue, CHAIN_ID_OFFSET),
+                Fr::from(mock::MOCK_CHAIN_ID),
+            )]),
+        ),
+        "chain_id == 0",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_failure_context() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress
+    let is_create_row = 1 + 5;
+    let failures = run_with_failure_context::<Fr>(
+        vec![build_pre_eip155_tx()],
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0,
+        HashMap::from([((AdviceColumn::TxValue, is_create_row), Fr::from(2))]),
+    )
+    .expect_err("IsCreate = 2 must be rejected");
+
+    assert!(
+        failures
+            .iter()
+            .any(|failure| failure.contains("IsCreate is boolean")
+                && failure.contains("[tx_id: 1, tag: IsCreate, index: 0]")),
+        "{failures:#?}"
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_skip_access_list_lookup() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 3200;
+
+    let tx = build_eip1559_tx(1);
+    assert!(tx.access_list.is_some());
+    // null row + 22 fixed rows before AccessListAddressesLen
+    let access_list_len_row = 1 + 22;
+
+    // a zero inverse would claim that the number of access list addresses is zero, and
+    // switch off the lookup to the access list dynamic section
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([
+                (
+                    (AdviceColumn::ValueIsZeroInv, access_list_len_row),
+                    Fr::zero(),
+                ),
+                (
+                    (AdviceColumn::TxAccessListCondition, access_list_len_row),
+                    Fr::zero(),
+                ),
+            ]),
+        ),
+        "is_zero gate",
+    );
+}
+
+/// The tx circuit checking that the L1 msgs of each block precede its L2 txs
+#[derive(Clone, Debug, Default)]
+struct L1MsgsFirst;
+
+impl TxCircuitTesterOptions for L1MsgsFirst {
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        TxCircuitTesterConfigArgs {
+            check_l1_msgs_first: true,
+            ..args
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_l1_msgs_first() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 3600;
+
+    let l1_msg = build_l1_msg_tx();
+    let start_l1_queue_index = l1_msg.nonce;
+    let run = |txs: Vec<Transaction>| {
+        let circuit = TxCircuitTester::<Fr, L1MsgsFirst>::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            mock::MOCK_CHAIN_ID,
+            start_l1_queue_index,
+            txs,
+        );
+        let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+        let k = max(20, log2_ceil(active_row_num));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        (
+            circuit.tx_circuit.validate_ordering(),
+            prover.verify_at_rows_par(0..active_row_num, 0..active_row_num),
+        )
+    };
+
+    // both txs are in block 1
+    let (ordering, result) = run(vec![l1_msg.clone(), build_eip1559_tx(2)]);
+    assert!(ordering.is_ok());
+    assert_eq!(result, Ok(()));
+
+    let mut misordered_l1_msg = l1_msg;
+    misordered_l1_msg.id = 2;
+    let (ordering, result) = run(vec![build_eip1559_tx(1), misordered_l1_msg]);
+    assert!(ordering.is_err());
+    assert_constraint_not_satisfied(result, "L2 tx is not followed by an L1 msg");
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_q_first_enabled_once() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let txs: Vec<Transaction> = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[2].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    let circuit = TxCircuitTester::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs);
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+
+    // q_first is a fixed column, so the single enable at the null row is part of the
+    // verifying key rather than something a gate has to count
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let q_first_rows: Vec<_> = prover.fixed()[config.tx_config.q_first.index()]
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| **cell == CellValue::Assigned(Fr::from(1)))
+        .map(|(row, _)| row)
+        .collect();
+    assert_eq!(q_first_rows, vec![0]);
+}
+
+/// Tx circuit which forgets to load the u16 table it shares with other circuits
+#[derive(Clone, Debug, Default)]
+struct TxCircuitWithoutU16Table(TxCircuit<Fr>);
+
+impl Circuit<Fr> for TxCircuitWithoutU16Table {
+    type Config = (TxCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        TxCircuitWithoutSigCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        let range_table_rows = RangeTableRows {
+            u8_rows: config.u8_table.load(&mut layouter)?,
+            u16_rows: 0,
+        };
+        self.0.synthesize_with_challenges(
+            &config,
+            &challenges,
+            Some(range_table_rows),
+            &mut layouter,
+        )
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_unloaded_u16_table() {
+    let l1_msg = build_l1_msg_tx();
+    let tx_circuit = TxCircuit::<Fr>::new(
+        1,
+        l1_msg.call_data.len(),
+        mock::MOCK_CHAIN_ID,
+        l1_msg.nonce,
+        vec![l1_msg],
+    );
+    let circuit = TxCircuitWithoutU16Table(tx_circuit);
+    let (config, _) = TxCircuitWithoutSigCircuit::configure(&mut ConstraintSystem::default());
+    assert!(matches!(
+        MockProver::run(circuit.0.recommended_k(&config), &circuit, vec![]),
+        Err(Error::Synthesis)
+    ));
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_decreasing_calldata_tx_id() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let txs: Vec<Transaction> = [
+        mock::CORRECT_MOCK_TXS[1].clone(),
+        mock::CORRECT_MOCK_TXS[2].clone(),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, mut tx)| {
+        tx.transaction_idx((i + 1) as u64);
+        tx.into()
+    })
+    .collect();
+    assert!(!txs[0].call_data.is_empty() && !txs[1].call_data.is_empty());
+
+    // the last calldata byte of the 1st tx claims tx_id 3, which is followed by the
+    // calldata of tx 2
+    let last_calldata_row = MAX_TXS * TX_LEN + txs[0].call_data.len();
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            txs,
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([((AdviceColumn::TxId, last_calldata_row), Fr::from(3))]),
+        ),
+        "tx_id::next - tx_id == sum(limb_i * 2^(16 * i))",
+    );
+}
+
+#[test]
+fn tx_circuit_column_order() {
+    // (name, index relative to the first column of its kind allocated by the tx circuit)
+    let column_indices = |max_tx_gas, check_l1_msgs_first| {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (args, _) = TxCircuitTesterConfigArgs::construct(&mut meta);
+        let config = TxCircuitTesterConfig::new(
+            &mut meta,
+            TxCircuitTesterConfigArgs {
+                max_tx_gas,
+                check_l1_msgs_first,
+                ..args
+            },
+        )
+        .tx_config;
+        let fixed = [
+            ("q_first", config.q_first),
+            ("q_calldata_first", config.q_calldata_first),
+            ("q_calldata_last", config.q_calldata_last),
+            ("q_padding", config.q_padding),
+        ]
+        .map(|(name, column)| (name, column.index() - config.q_first.index()))
+        .to_vec();
+        let lookup_condition = |name, condition| (name, config.lookup_conditions[&condition]);
+        let advice = [
+            ("tx_nonce", config.tx_nonce),
+            ("block_num", config.block_num),
+            ("total_l1_popped_before", config.total_l1_popped_before),
+            ("num_all_txs_acc", config.num_all_txs_acc),
+            ("tx_type", config.tx_type),
+            ("rlp_tag", config.rlp_tag),
+            ("tx_value_rlc", config.tx_value_rlc),
+            ("tx_value_length", config.tx_value_length),
+            ("is_none", config.is_none),
+            ("cum_num_txs", config.cum_num_txs),
+            ("num_txs", config.num_txs),
+            ("is_padding_tx", config.is_padding_tx),
+            ("is_final", config.is_final),
+            ("calldata_gas_cost_acc", config.calldata_gas_cost_acc),
+            ("section_rlc", config.section_rlc),
+            ("calldata_byte", config.calldata_byte),
+            ("is_l1_msg", config.is_l1_msg),
+            ("is_eip2930", config.is_eip2930),
+            ("is_eip1559", config.is_eip1559),
+            ("is_calldata", config.is_calldata),
+            ("is_tx_id_zero", config.is_tx_id_zero),
+            ("is_caller_address", config.is_caller_address),
+            ("is_chain_id", config.is_chain_id),
+            ("is_tag_block_num", config.is_tag_block_num),
+            lookup_condition("TxCalldata", LookupCondition::TxCalldata),
+            lookup_condition("L1MsgHash", LookupCondition::L1MsgHash),
+            lookup_condition("RlpSignTag", LookupCondition::RlpSignTag),
+            lookup_condition("RlpHashTag", LookupCondition::RlpHashTag),
+            lookup_condition("Keccak", LookupCondition::Keccak),
+            lookup_condition("TxAccessList", LookupCondition::TxAccessList),
+            ("sv_address", config.sv_address),
+            ("num_l1_msgs", config.num_l1_msgs),
+            ("num_l2_txs", config.num_l2_txs),
+            ("num_txs_acc", config.num_txs_acc),
+        ]
+        .into_iter()
+        .chain(config.address_limbs.map(|limb| ("address_limbs", limb)))
+        .map(|(name, column)| (name, column.index() - config.tx_nonce.index()))
+        .collect::<Vec<_>>();
+
+        (fixed, advice)
+    };
+
+    let fixed = vec![
+        ("q_first", 0),
+        ("q_calldata_first", 1),
+        ("q_calldata_last", 2),
+        ("q_padding", 3),
+    ];
+    let advice = [
+        ("tx_nonce", 0),
+        ("block_num", 1),
+        ("total_l1_popped_before", 2),
+        ("num_all_txs_acc", 3),
+        ("tx_type", 4),
+        ("rlp_tag", 5),
+        ("tx_value_rlc", 6),
+        ("tx_value_length", 7),
+        ("is_none", 8),
+        // 9..17: the bits of tx_tag_bits and tx_type_bits
+        ("cum_num_txs", 17),
+        ("num_txs", 18),
+        ("is_padding_tx", 19),
+        ("is_final", 20),
+        ("calldata_gas_cost_acc", 21),
+        ("section_rlc", 22),
+        ("calldata_byte", 23),
+        ("is_l1_msg", 24),
+        ("is_eip2930", 25),
+        ("is_eip1559", 26),
+        ("is_calldata", 27),
+        ("is_tx_id_zero", 28),
+        ("is_caller_address", 29),
+        ("is_chain_id", 30),
+        ("is_tag_block_num", 31),
+        ("TxCalldata", 32),
+        ("L1MsgHash", 33),
+        ("RlpSignTag", 34),
+        ("RlpHashTag", 35),
+        ("Keccak", 36),
+        ("TxAccessList", 37),
+        // 38..45: the access list columns
+        ("sv_address", 45),
+        // 46..57: the columns of tx_id_is_zero, tx_id_unchanged, value_is_zero,
+        // block_num_unchanged, tx_id_gt_prev_cnt and tx_id_cmp_cum_num_txs
+        ("num_l1_msgs", 57),
+        ("num_l2_txs", 58),
+        ("num_txs_acc", 59),
+    ]
+    .into_iter()
+    .chain((60..70).map(|index| ("address_limbs", index)))
+    .collect::<Vec<_>>();
+    assert_eq!(column_indices(None, false), (fixed.clone(), advice.clone()));
+
+    // enabling the optional features appends columns without shifting the others
+    assert_eq!(column_indices(Some(1_000_000), true), (fixed, advice));
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_num_all_txs_lookup_at_last_tx() {
+    const MAX_TXS: usize = 3;
+    const MAX_CALLDATA: usize = 32;
+
+    // two txs in block 1, one tx in block 2
+    let txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[1..4]
+        .iter()
+        .zip([1, 1, 2])
+        .enumerate()
+        .map(|(i, (tx, block_number))| {
+            let mut tx: Transaction = tx.clone().transaction_idx((i + 1) as u64).clone().into();
+            tx.block_number = block_number;
+            tx
+        })
+        .collect();
+    let run = |overrides| {
+        run_with_overrides::<Fr>(
+            txs.clone(),
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        )
+    };
+
+    // the lookup only holds if it fires once per block, at its last tx: the 1st tx has
+    // num_all_txs_acc = 1 while block 1 has 2 txs
+    assert_eq!(run(HashMap::new()), Ok(()));
+
+    // the block number changes after the 1st tx, so the lookup fires at it as well
+    let block_num_row = TX_LEN;
+    let errors = run(HashMap::from([(
+        (AdviceColumn::BlockNum, block_num_row),
+        Fr::from(2),
+    )]))
+    .expect_err("the lookup fires twice for block 1");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup {
+                name,
+                location: FailureLocation::InRegion { offset, .. },
+                ..
+            } if name == "num_all_txs in block table" && *offset == block_num_row
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_circuit_sig_circuit_capacity() {
+    let l1_msg = build_l1_msg_tx();
+    let mut l2_tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(2)
+        .clone()
+        .into();
+    l2_tx.block_number = l1_msg.block_number;
+    let start_l1_queue_index = l1_msg.nonce;
+    let tx_circuit = |max_txs, txs| {
+        TxCircuit::<Fr>::new(max_txs, 320, mock::MOCK_CHAIN_ID, start_l1_queue_index, txs)
+    };
+
+    for (max_txs, txs) in [
+        (4, vec![l1_msg.clone(), l2_tx.clone()]),
+        (2, vec![l1_msg.clone(), l2_tx]),
+        (2, vec![l1_msg.clone()]),
+        (1, vec![l1_msg]),
+    ] {
+        let tx_circuit = tx_circuit(max_txs, txs);
+        let capacity = tx_circuit.sig_circuit_capacity().unwrap();
+        // a sig circuit sized from the capacity holds all the sig table inputs
+        assert_eq!(capacity, tx_circuit.sig_table_inputs().unwrap().len());
+        assert_eq!(capacity, max_txs);
+    }
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_null_rlp_tag_lookup() {
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress
+    let is_create_row = 1 + 5;
+    let overrides = HashMap::from([(
+        (AdviceColumn::RlpHashTagCondition, is_create_row),
+        Fr::one(),
+    )]);
+
+    let tx = build_pre_eip155_tx();
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, 1, 320, 0, overrides.clone()),
+        "rlp_tag == Null => no lookup into RLP table",
+    );
+
+    // the hash tag condition gate doesn't cover L1 msgs
+    let l1_msg = build_l1_msg_tx();
+    let (max_calldata, start_l1_queue_index) = (l1_msg.call_data.len(), l1_msg.nonce);
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![l1_msg],
+            mock::MOCK_CHAIN_ID,
+            1,
+            max_calldata,
+            start_l1_queue_index,
+            overrides,
+        ),
+        "rlp_tag == Null => no lookup into RLP table",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_calldata_length_mismatch() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx = mock::CORRECT_MOCK_TXS[1].clone();
+    tx.transaction_idx(1);
+    let tx: Transaction = tx.into();
+    assert!(!tx.call_data.is_empty());
+
+    // the tx claims to have no calldata, but its bytes are still in the calldata region
+    // null row + Nonce, GasPrice, Gas, CallerAddress, CalleeAddress, IsCreate, Value,
+    // CallDataRLC
+    let calldata_length_row = 1 + 8;
+    let overrides = HashMap::from([
+        ((AdviceColumn::TxValue, calldata_length_row), Fr::zero()),
+        (
+            (AdviceColumn::ValueIsZeroInv, calldata_length_row),
+            Fr::zero(),
+        ),
+        (
+            (AdviceColumn::TxCalldataCondition, calldata_length_row),
+            Fr::zero(),
+        ),
+    ]);
+    let errors = run_with_overrides::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID,
+        MAX_TXS,
+        MAX_CALLDATA,
+        0,
+        overrides,
+    )
+    .expect_err("the calldata bytes don't match CallDataLength");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "calldata length in TxTable"
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_two_final_calldata_bytes() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx = mock::CORRECT_MOCK_TXS[1].clone();
+    tx.transaction_idx(1);
+    let tx: Transaction = tx.into();
+    assert!(tx.call_data.len() > 1);
+
+    // the first calldata byte is final as well as the last one
+    let calldata_first_row = MAX_TXS * TX_LEN + 1;
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([((AdviceColumn::IsFinal, calldata_first_row), Fr::one())]),
+        ),
+        "tx_id changes at is_final == 1",
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_tx_type_changed_mid_tx() {
+    let tx = build_pre_eip155_tx();
+    assert_eq!(tx.tx_type, TxType::PreEip155);
+
+    // null row + Nonce, GasPrice, Gas, CallerAddress
+    let callee_address_row = 1 + 4;
+    let overrides = HashMap::from([(
+        (AdviceColumn::TxType, callee_address_row),
+        Fr::from(u64::from(TxType::Eip155)),
+    )]);
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, 1, 320, 0, overrides),
+        "('tx_type')",
+    );
+}
+
+#[cfg(debug_assertions)]
+fn reference_conditions(tx: &Transaction, tx_tag: TxFieldTag) -> BTreeMap<LookupCondition, Fr> {
+    reference_lookup_conditions(tx, tx_tag)
+        .into_iter()
+        .map(|(condition, value)| (condition, Fr::from(value as u64)))
+        .collect()
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn tx_circuit_lookup_conditions_reference() {
+    let tx = build_pre_eip155_tx();
+    // pre-eip155 txs don't sign the chain id
+    let conditions = reference_conditions(&tx, ChainID);
+    assert_eq!(conditions[&LookupCondition::RlpSignTag], Fr::zero());
+    assert_lookup_conditions(&tx, ChainID, &conditions);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(
+    expected = "lookup condition RlpSignTag of tx 1 at tag Nonce differs from the reference"
+)]
+fn tx_circuit_wrong_lookup_condition() {
+    let tx = build_pre_eip155_tx();
+    let mut conditions = reference_conditions(&tx, Nonce);
+    conditions.insert(LookupCondition::RlpSignTag, Fr::zero());
+    assert_lookup_conditions(&tx, Nonce, &conditions);
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_sender_recovery_without_tx_layout() {
+    let tx = build_pre_eip155_tx();
+    let tx_circuit = TxCircuit::<Fr>::new(1, 320, mock::MOCK_CHAIN_ID, 0, vec![tx.clone()]);
+    let signatures = tx_circuit.sig_table_inputs().unwrap();
+    assert_eq!(signatures.len(), 1);
+    assert_eq!(signatures[0].get_addr(), tx.caller_address);
+
+    let circuit = SigCircuit::<Fr> {
+        max_verif: signatures.len(),
+        signatures,
+        _marker: PhantomData,
+    };
+    let prover = MockProver::run(20, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify_par(), Ok(()));
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_block_num_unchanged_matches_block_nums() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 32;
+
+    // blocks 1, 1, 2 and a padding tx
+    let txs: Vec<Transaction> = mock::CORRECT_MOCK_TXS[1..4]
+        .iter()
+        .zip([1, 1, 2])
+        .enumerate()
+        .map(|(i, (tx, block_number))| {
+            let mut tx: Transaction = tx.clone().transaction_idx((i + 1) as u64).clone().into();
+            tx.block_number = block_number;
+            tx
+        })
+        .collect();
+    let run = |overrides| {
+        run_with_overrides::<Fr>(
+            txs.clone(),
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            overrides,
+        )
+    };
+    assert_eq!(run(HashMap::new()), Ok(()));
+
+    // block_num_unchanged is assigned for the block numbers 1 and 1 at the BlockNumber row
+    // of the 1st tx, which then disagree with the block number 2 of the next row
+    let block_num_row = TX_LEN;
+    let errors = run(HashMap::from([(
+        (AdviceColumn::BlockNum, block_num_row + 1),
+        Fr::from(2),
+    )]))
+    .expect_err("block_num_unchanged disagrees with the block numbers");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied {
+                constraint,
+                location: FailureLocation::InRegion { offset, .. },
+                ..
+            } if format!("{constraint}").contains("is_zero gate") && *offset == block_num_row
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_circuit_pi_inputs() {
+    let l1_msg = build_l1_msg_tx();
+    let mut l2_tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(2)
+        .clone()
+        .into();
+    l2_tx.block_number = l1_msg.block_number;
+    let block_number = l1_msg.block_number;
+    let tx_circuit = TxCircuit::<Fr>::new(
+        4,
+        320,
+        mock::MOCK_CHAIN_ID,
+        l1_msg.nonce,
+        vec![l1_msg, l2_tx],
+    );
+
+    let pi_inputs = tx_circuit.pi_inputs();
+    assert_eq!(pi_inputs.num_txs_per_block, tx_circuit.num_txs_per_block());
+    assert_eq!(pi_inputs.tx_hashes, tx_circuit.tx_hashes());
+    assert_eq!(pi_inputs.l1_msg_flags, tx_circuit.l1_msg_flags());
+
+    assert_eq!(
+        pi_inputs.num_txs_per_block,
+        BTreeMap::from([(block_number, (1, 1))])
+    );
+    // padding txs are not included
+    assert_eq!(pi_inputs.tx_hashes.len(), 2);
+    assert_eq!(pi_inputs.l1_msg_flags, vec![true, false]);
+}
+
+#[test]
+fn tx_circuit_calldata_zeros_section_rlc() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx = mock::CORRECT_MOCK_TXS[1].clone();
+    tx.transaction_idx(1);
+    let tx: Transaction = tx.into();
+    assert!(tx.call_data.len() < MAX_CALLDATA);
+
+    // the first row of the calldata zeros after the calldata of the tx
+    let zero_row = MAX_TXS * TX_LEN + 1 + tx.call_data.len();
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([((AdviceColumn::SectionRlc, zero_row), Fr::one())]),
+        ),
+        "('section_rlc == 0')",
+    );
+}
+
+#[test]
+fn tx_circuit_sig_r_s_rlc_matches_sig_table() {
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    let sign_data = tx.sign_data().unwrap();
+
+    // the tx table holds r and s as the RLC of their big-endian bytes, the sig table as
+    // the RLC of their little-endian bytes with increasing powers of the same challenge
+    let evm_word = Fr::from(0x100001);
+    let challenges = Challenges::mock(
+        Value::known(evm_word),
+        Value::known(Fr::from(0x100003)),
+        Value::known(Fr::from(0x100005)),
+    );
+    let tx_value = |tag: TxFieldTag| {
+        let (_, _, value) = tx_fixed_rows(&tx, &TxHasher::default(), &challenges)
+            .into_iter()
+            .find(|(row_tag, _, _)| *row_tag == tag)
+            .unwrap();
+        unwrap_value(value)
+    };
+    let sig_table_rlc = |bytes: [u8; 32]| rlc::value(bytes.iter(), evm_word);
+    assert_eq!(
+        tx_value(SigR),
+        sig_table_rlc(sign_data.signature.0.to_bytes())
+    );
+    assert_eq!(
+        tx_value(SigS),
+        sig_table_rlc(sign_data.signature.1.to_bytes())
+    );
+
+    assert_eq!(run::<Fr>(vec![tx], mock::MOCK_CHAIN_ID, 1, 320, 0), Ok(()));
+}
+
+#[test]
+fn tx_circuit_padding_txs_lower_block_num() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 32;
+
+    let txs = |block_numbers: [u64; 2]| -> Vec<Transaction> {
+        mock::CORRECT_MOCK_TXS[1..3]
+            .iter()
+            .zip(block_numbers)
+            .enumerate()
+            .map(|(i, (tx, block_number))| {
+                let mut tx: Transaction = tx.clone().transaction_idx((i + 1) as u64).clone().into();
+                tx.block_number = block_number;
+                tx
+            })
+            .collect()
+    };
+    let run = |txs| run::<Fr>(txs, mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0);
+
+    // the padding txs are in block 0, which is lower than the blocks of the real txs,
+    // but the lookup is disabled when the next tx is a padding tx
+    let padding_txs = get_padding_txs(2, MAX_TXS, mock::MOCK_CHAIN_ID);
+    assert!(padding_txs.iter().all(|tx| tx.block_number == 0));
+    assert_eq!(run(txs([10, 11])), Ok(()));
+
+    // between real txs the lookup still rejects a decreasing block number
+    let errors = run(txs([11, 10])).expect_err("block 10 follows block 11");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "block_num is non-decreasing till padding txs"
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_circuit_tx_sign_hash_is_sig_table_msg_hash() {
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+
+    // the sig table lookup on the ChainID row reads msg_hash_rlc from the TxSignHash row
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100001)),
+        Value::known(Fr::from(0x100003)),
+        Value::known(Fr::from(0x100005)),
+    );
+    let tags = tx_fixed_rows(&tx, &TxHasher::default(), &challenges)
+        .into_iter()
+        .map(|(tag, _, _)| tag)
+        .collect::<Vec<_>>();
+    assert_eq!(tags[CHAIN_ID_OFFSET - 1], ChainID);
+    assert_eq!(tags[TX_SIGN_HASH_OFFSET - 1], TxSignHash);
+
+    // null row + the rows before TxSignHash of the 1st tx
+    let tx_sign_hash_row = TX_SIGN_HASH_OFFSET;
+    let errors = run_with_overrides::<Fr>(
+        vec![tx],
+        mock::MOCK_CHAIN_ID,
+        1,
+        320,
+        0,
+        HashMap::from([((AdviceColumn::TxValue, tx_sign_hash_row), Fr::one())]),
+    )
+    .expect_err("the TxSignHash row is not the signed message hash");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup {
+                name,
+                location: FailureLocation::InRegion { offset, .. },
+                ..
+            } if name == "Sig table lookup" && *offset == CHAIN_ID_OFFSET
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_circuit_forged_l1_msg_sender() {
+    let l1_msg = build_l1_msg_tx();
+    let start_l1_queue_index = l1_msg.nonce;
+    assert_ne!(l1_msg.caller_address, mock::MOCK_ACCOUNTS[0]);
+
+    // null row + Nonce, GasPrice, Gas
+    let caller_address_row = 1 + 3;
+    let errors = run_with_overrides::<Fr>(
+        vec![l1_msg],
+        mock::MOCK_CHAIN_ID,
+        1,
+        320,
+        start_l1_queue_index,
+        HashMap::from([(
+            (AdviceColumn::TxValue, caller_address_row),
+            mock::MOCK_ACCOUNTS[0].to_scalar().unwrap(),
+        )]),
+    )
+    .expect_err("the sender is not the one in the RLP encoding of the L1 msg");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup {
+                name,
+                location: FailureLocation::InRegion { offset, .. },
+                ..
+            } if name == "lookup tx tag in RLP Table for hashing" && *offset == caller_address_row
+        )),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn tx_circuit_fixed_tag_pattern() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100001)),
+        Value::known(Fr::from(0x100003)),
+        Value::known(Fr::from(0x100005)),
+    );
+    let tags = tx_fixed_rows(&tx, &TxHasher::default(), &challenges)
+        .into_iter()
+        .map(|(tag, _, _)| CellValue::Assigned(Fr::from(usize::from(tag) as u64)))
+        .collect::<Vec<_>>();
+    assert_eq!(tags.len(), TX_LEN);
+
+    let circuit =
+        TxCircuitTester::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx]);
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+
+    // the tag column is fixed, so the pattern of TX_LEN tags repeating for the real tx
+    // and the padding tx is part of the verifying key
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let tag_column = &prover.fixed()[config.tx_config.tx_table.tag.index()];
+    for i in 0..MAX_TXS {
+        let start = i * TX_LEN + 1;
+        assert_eq!(
+            tag_column[start..start + TX_LEN],
+            tags[..],
+            "tags of tx {}",
+            i + 1
+        );
+    }
+}
+
+/// Gas costs of the call data bytes before Istanbul
+const PRE_ISTANBUL_CALLDATA_GAS_COSTS: CalldataGasCosts = CalldataGasCosts {
+    zero_byte: 4,
+    non_zero_byte: 68,
+};
+
+/// The tx circuit with the gas costs of the call data bytes before Istanbul
+#[derive(Clone, Debug, Default)]
+struct PreIstanbulCalldataGas;
+
+impl TxCircuitTesterOptions for PreIstanbulCalldataGas {
+    fn config_args<F: Field>(args: TxCircuitTesterConfigArgs<F>) -> TxCircuitTesterConfigArgs<F> {
+        TxCircuitTesterConfigArgs {
+            calldata_gas_costs: PRE_ISTANBUL_CALLDATA_GAS_COSTS,
+            ..args
+        }
+    }
+}
+
+#[test]
+fn tx_circuit_pre_istanbul_calldata_gas_costs() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    assert!(tx.call_data.iter().any(|byte| *byte != 0));
+    let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+    let k = max(20, log2_ceil(active_row_num));
+    let verify = |tx: &Transaction| {
+        let circuit = TxCircuitTester::<Fr, PreIstanbulCalldataGas>::new(
+            MAX_TXS,
+            MAX_CALLDATA,
+            mock::MOCK_CHAIN_ID,
+            0,
+            vec![tx.clone()],
+        );
+        MockProver::run(k, &circuit, vec![])
+            .unwrap()
+            .verify_at_rows_par(0..active_row_num, 0..active_row_num)
+    };
+
+    // the CallDataGasCost of the tx is computed with 16 gas per non-zero byte
+    assert_eq!(tx.call_data_gas_cost, tx_data_gas_cost(&tx.call_data));
+    let errors = verify(&tx).expect_err("the call data gas costs differ");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::Lookup { name, .. } if name == "tx call data gas cost in TxTable"
+        )),
+        "{errors:?}"
+    );
+
+    tx.call_data_gas_cost = PRE_ISTANBUL_CALLDATA_GAS_COSTS.data_cost(&tx.call_data);
+    assert_eq!(verify(&tx), Ok(()));
+}
+
+#[test]
+fn tx_circuit_sig_lookup_rotations() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let value_column = config.tx_config.tx_table.value.index();
+
+    // the rotations of the tx values read by the sig table lookup from the ChainID row
+    let lookup = meta
+        .lookups
+        .iter()
+        .find(|lookup| lookup.name() == "Sig table lookup")
+        .expect("the sig table lookup is configured");
+    let rotations = lookup
+        .input_expressions()
+        .iter()
+        .flat_map(|expr| {
+            expr.evaluate(
+                &|_| BTreeSet::new(),
+                &|_| BTreeSet::new(),
+                &|_| BTreeSet::new(),
+                &|query| {
+                    if query.column_index() == value_column {
+                        BTreeSet::from([query.rotation().0])
+                    } else {
+                        BTreeSet::new()
+                    }
+                },
+                &|_| BTreeSet::new(),
+                &|_| BTreeSet::new(),
+                &|a| a,
+                &|mut a, b| {
+                    a.extend(b);
+                    a
+                },
+                &|mut a, b| {
+                    a.extend(b);
+                    a
+                },
+                &|a, _| a,
+            )
+        })
+        .collect::<BTreeSet<i32>>();
+
+    // they land on the tags of the values looked up in the canonical layout
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[0]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100001)),
+        Value::known(Fr::from(0x100003)),
+        Value::known(Fr::from(0x100005)),
+    );
+    let tags = tx_fixed_rows(&tx, &TxHasher::default(), &challenges)
+        .into_iter()
+        .map(|(tag, _, _)| tag)
+        .collect::<Vec<_>>();
+    let chain_id_index = CHAIN_ID_OFFSET - 1;
+    assert_eq!(tags[chain_id_index], ChainID);
+    let looked_up_tags = rotations
+        .into_iter()
+        .map(|rotation| tags[(chain_id_index as i32 + rotation) as usize])
+        .collect::<Vec<_>>();
+    assert_eq!(looked_up_tags, vec![ChainID, SigV, SigR, SigS, TxSignHash]);
+}
+
+#[test]
+fn tx_circuit_assignment_export() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    let circuit =
+        TxCircuitTester::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, vec![tx]);
+    let size = circuit.tx_circuit.size;
+    let k = max(20, log2_ceil(size));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify_par(), Ok(()));
+
+    // the tx table rows after the null row: the fixed rows followed by the calldata rows
+    let export = circuit
+        .tx_circuit
+        .assignment_export()
+        .expect("export is recorded")
+        .with_offsets(1..1 + size);
+    assert_eq!(export.offsets(), (1..1 + size).collect::<Vec<_>>());
+
+    // the tx table columns are assigned at each row
+    let config = TxCircuitTester::<Fr>::configure(&mut ConstraintSystem::default())
+        .0
+        .tx_config;
+    for column in [
+        TxAssignmentColumn::Fixed(config.tx_table.tag.index()),
+        TxAssignmentColumn::Advice(config.tx_table.tx_id.index()),
+        TxAssignmentColumn::Advice(config.tx_table.value.index()),
+    ] {
+        assert!(export.columns().contains(&column), "{column}");
+        assert!((1..1 + size).all(|offset| export.cells.contains_key(&(offset, column))));
+    }
+
+    let mut csv = vec![];
+    export.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 1 + size);
+    assert!(csv
+        .lines()
+        .all(|line| line.split(',').count() == 1 + export.columns().len()));
+}
+
+#[test]
+fn tx_circuit_tx_id_is_zero_consistent() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let (config, _) = TxCircuitTester::<Fr>::configure(&mut meta);
+    let config = config.tx_config;
+
+    let tx: Transaction = mock::CORRECT_MOCK_TXS[1]
+        .clone()
+        .transaction_idx(1)
+        .clone()
+        .into();
+    // without txs (only padding txs and calldata zeros) and with a tx with calldata
+    for txs in [vec![], vec![tx.clone()]] {
+        let circuit =
+            TxCircuitTester::<Fr>::new(MAX_TXS, MAX_CALLDATA, mock::MOCK_CHAIN_ID, 0, txs);
+        let active_row_num = TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA);
+        let k = max(20, log2_ceil(active_row_num));
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        let advice =
+            |column: Column<Advice>, row: usize| match &prover.advice()[column.index()][row] {
+                CellValue::Assigned(value) => *value,
+                _ => Fr::zero(),
+            };
+
+        // the null row, the fixed rows and the calldata rows, including the calldata zeros
+        let rows = 0..1 + active_row_num;
+        assert!(rows.clone().all(|row| {
+            prover.fixed()[config.tx_table.q_enable.index()][row] == CellValue::Assigned(Fr::one())
+        }));
+        for row in rows {
+            let tx_id = advice(config.tx_table.tx_id, row);
+            let is_zero = Fr::one() - tx_id * advice(config.tx_id_is_zero.value_inv(), row);
+            let expected = Fr::from((tx_id == Fr::zero()) as u64);
+            assert_eq!(is_zero, expected, "tx_id_is_zero at row {row}");
+            assert_eq!(
+                advice(config.is_tx_id_zero, row),
+                expected,
+                "is_tx_id_zero at row {row}"
+            );
+        }
+    }
+
+    // tx id 1 at the Nonce row of the 1st tx can't be claimed to be zero
+    assert_constraint_not_satisfied(
+        run_with_overrides::<Fr>(
+            vec![tx],
+            mock::MOCK_CHAIN_ID,
+            MAX_TXS,
+            MAX_CALLDATA,
+            0,
+            HashMap::from([((AdviceColumn::TxIdInv, 1), Fr::zero())]),
+        ),
+        "('is_tx_id_zero')",
     );
 }
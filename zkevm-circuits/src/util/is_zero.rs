@@ -26,6 +26,11 @@ impl<F: Field> IsZeroConfig<F> {
             1.expr() - meta.query_advice(value, rotation) * meta.query_advice(value_inv, rotation)
         }
     }
+
+    /// Returns the column of the inverse of the value
+    pub(crate) fn value_inv(&self) -> Column<Advice> {
+        self.config.value_inv
+    }
 }
 
 /// This chip is a wrapper of IsZeroChip in gadgets.
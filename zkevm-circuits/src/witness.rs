@@ -32,7 +32,9 @@ pub use rw::{Rw, RwMap, RwRow};
 mod step;
 pub use step::ExecStep;
 
+mod compact_tx;
 mod l1_msg;
 mod tx;
 
-pub use tx::Transaction;
+pub use compact_tx::CompactTransaction;
+pub use tx::{RecoveryCache, RecoveryKey, RlpStream, Transaction};
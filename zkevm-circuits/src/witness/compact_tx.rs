@@ -0,0 +1,302 @@
+use bus_mapping::circuit_input_builder::TxL1Fee;
+use eth_types::{
+    evm_types::gas_utils::{tx_access_list_gas_cost, tx_data_gas_cost},
+    geth_types::TxType,
+    AccessList, Address, Signature, Word, H256,
+};
+use ethers_core::{
+    types::{
+        transaction::{eip2718::TypedTransaction, eip2930::Eip2930TransactionRequest},
+        Eip1559TransactionRequest, TransactionRequest,
+    },
+    utils::rlp::RlpStream,
+};
+
+use super::Transaction;
+
+/// Compact representation of a [`Transaction`] which only keeps the essential
+/// fields of a tx. The RLP encodings and the gas costs derived from them are
+/// computed on demand, and the execution trace (calls and steps) is dropped, so
+/// that a block of txs takes less memory before it is assigned.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CompactTransaction {
+    /// The block number in which this tx is included in
+    pub block_number: u64,
+    /// The transaction identifier in the block
+    pub id: usize,
+    /// The hash of the transaction
+    pub hash: H256,
+    /// The type of the transaction
+    pub tx_type: TxType,
+    /// The sender account nonce of the transaction
+    pub nonce: u64,
+    /// The gas limit of the transaction
+    pub gas: u64,
+    /// The gas price
+    pub gas_price: Word,
+    /// Max fee per gas (EIP1559)
+    pub max_fee_per_gas: Word,
+    /// Max priority fee per gas (EIP1559)
+    pub max_priority_fee_per_gas: Word,
+    /// The caller address
+    pub caller_address: Address,
+    /// The callee address
+    pub callee_address: Option<Address>,
+    /// The ether amount of the transaction
+    pub value: Word,
+    /// The call data
+    pub call_data: Vec<u8>,
+    /// Chain ID as per EIP-155.
+    pub chain_id: u64,
+    /// "v" value of the transaction signature
+    pub v: u64,
+    /// "r" value of the transaction signature
+    pub r: Word,
+    /// "s" value of the transaction signature
+    pub s: Word,
+    /// Current values of L1 fee
+    pub l1_fee: TxL1Fee,
+    /// Committed values of L1 fee
+    pub l1_fee_committed: TxL1Fee,
+    /// Optional access list for EIP-2930
+    pub access_list: Option<AccessList>,
+}
+
+impl CompactTransaction {
+    fn signature(&self) -> Signature {
+        Signature {
+            r: self.r,
+            s: self.s,
+            v: self.v,
+        }
+    }
+
+    fn legacy_tx(&self) -> TransactionRequest {
+        let mut tx = TransactionRequest::new()
+            .from(self.caller_address)
+            .nonce(self.nonce)
+            .gas_price(self.gas_price)
+            .gas(self.gas)
+            .value(self.value)
+            .data(self.call_data.clone());
+        if let Some(to) = self.callee_address {
+            tx = tx.to(to);
+        }
+        if self.tx_type.is_eip155() {
+            tx = tx.chain_id(self.chain_id);
+        }
+        tx
+    }
+
+    fn typed_tx(&self) -> TypedTransaction {
+        match self.tx_type {
+            TxType::Eip2930 => Eip2930TransactionRequest::new(
+                self.legacy_tx().chain_id(self.chain_id),
+                self.access_list.clone().unwrap_or_default(),
+            )
+            .into(),
+            TxType::Eip1559 => {
+                let mut tx = Eip1559TransactionRequest::new()
+                    .from(self.caller_address)
+                    .nonce(self.nonce)
+                    .max_priority_fee_per_gas(self.max_priority_fee_per_gas)
+                    .max_fee_per_gas(self.max_fee_per_gas)
+                    .gas(self.gas)
+                    .value(self.value)
+                    .data(self.call_data.clone())
+                    .access_list(self.access_list.clone().unwrap_or_default())
+                    .chain_id(self.chain_id);
+                if let Some(to) = self.callee_address {
+                    tx = tx.to(to);
+                }
+                tx.into()
+            }
+            _ => unreachable!("tx type {:?} is not typed", self.tx_type),
+        }
+    }
+
+    /// Rlp-encoded bytes of the unsigned tx. L1 msgs are not signed, so it's empty
+    /// for them.
+    pub fn rlp_unsigned(&self) -> Vec<u8> {
+        match self.tx_type {
+            TxType::PreEip155 => self.legacy_tx().rlp_unsigned().to_vec(),
+            TxType::Eip155 => self.legacy_tx().rlp().to_vec(),
+            TxType::Eip2930 | TxType::Eip1559 => self.typed_tx().rlp().to_vec(),
+            TxType::L1Msg => vec![],
+        }
+    }
+
+    /// Rlp-encoded bytes of the signed tx
+    pub fn rlp_signed(&self) -> Vec<u8> {
+        match self.tx_type {
+            TxType::PreEip155 | TxType::Eip155 => {
+                self.legacy_tx().rlp_signed(&self.signature()).to_vec()
+            }
+            TxType::Eip2930 | TxType::Eip1559 => {
+                self.typed_tx().rlp_signed(&self.signature()).to_vec()
+            }
+            TxType::L1Msg => {
+                // 0x7E || rlp([nonce, gas, to, value, data, sender])
+                let mut stream = RlpStream::new_list(6);
+                stream.append(&self.nonce);
+                stream.append(&self.gas);
+                match self.callee_address {
+                    Some(to) => stream.append(&to),
+                    None => stream.append_empty_data(),
+                };
+                stream.append(&self.value);
+                stream.append(&self.call_data);
+                stream.append(&self.caller_address);
+
+                std::iter::once(0x7E).chain(stream.out()).collect()
+            }
+        }
+    }
+}
+
+impl From<&Transaction> for CompactTransaction {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            block_number: tx.block_number,
+            id: tx.id,
+            hash: tx.hash,
+            tx_type: tx.tx_type,
+            nonce: tx.nonce,
+            gas: tx.gas,
+            gas_price: tx.gas_price,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            caller_address: tx.caller_address,
+            callee_address: tx.callee_address,
+            value: tx.value,
+            call_data: tx.call_data.clone(),
+            chain_id: tx.chain_id,
+            v: tx.v,
+            r: tx.r,
+            s: tx.s,
+            l1_fee: tx.l1_fee,
+            l1_fee_committed: tx.l1_fee_committed,
+            access_list: tx.access_list.clone(),
+        }
+    }
+}
+
+impl From<&CompactTransaction> for Transaction {
+    fn from(tx: &CompactTransaction) -> Self {
+        let rlp_signed = tx.rlp_signed();
+        let tx_data_gas_cost = if tx.tx_type.is_l1_msg() {
+            0
+        } else {
+            tx_data_gas_cost(&rlp_signed)
+        };
+
+        Self {
+            block_number: tx.block_number,
+            id: tx.id,
+            hash: tx.hash,
+            tx_type: tx.tx_type,
+            nonce: tx.nonce,
+            gas: tx.gas,
+            gas_price: tx.gas_price,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            caller_address: tx.caller_address,
+            callee_address: tx.callee_address,
+            is_create: tx.callee_address.is_none(),
+            value: tx.value,
+            call_data: tx.call_data.clone(),
+            call_data_length: tx.call_data.len(),
+            call_data_gas_cost: tx_data_gas_cost(&tx.call_data),
+            access_list_gas_cost: tx_access_list_gas_cost(&tx.access_list),
+            tx_data_gas_cost,
+            chain_id: tx.chain_id,
+            rlp_unsigned: tx.rlp_unsigned(),
+            rlp_signed,
+            v: tx.v,
+            r: tx.r,
+            s: tx.s,
+            l1_fee: tx.l1_fee,
+            l1_fee_committed: tx.l1_fee_committed,
+            access_list: tx.access_list.clone(),
+            calls: vec![],
+            steps: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompactTransaction;
+    use crate::{tx_circuit::TxHasher, util::Challenges, witness::Transaction};
+    use eth_types::{geth_types::TxType, Word};
+    use ethers_core::{
+        types::Transaction as EthTransaction,
+        utils::{
+            keccak256,
+            rlp::{Decodable, Rlp},
+        },
+    };
+    use halo2_proofs::{circuit::Value, dev::unwrap_value, halo2curves::bn256::Fr};
+    use mock::{MockTransaction, MOCK_ACCOUNTS};
+
+    fn table_assignments(tx: &Transaction) -> Vec<[Fr; 5]> {
+        let challenges = Challenges::mock(
+            Value::known(Fr::from(0x1ab)),
+            Value::known(Fr::from(0x10000)),
+            Value::known(Fr::from(0x100)),
+        );
+        tx.table_assignments_fixed(&TxHasher::default(), challenges)
+            .into_iter()
+            .chain(tx.table_assignments_dyn(challenges))
+            .map(|row| row.map(unwrap_value))
+            .collect()
+    }
+
+    #[test]
+    fn compact_tx_round_trip() {
+        let tx: Transaction = MockTransaction::default()
+            .to(MOCK_ACCOUNTS[0])
+            .value(Word::from(0x2a))
+            .gas_price(Word::from(0x3b9aca00))
+            .input(vec![0xde, 0xad, 0xbe, 0xef].into())
+            .build()
+            .into();
+
+        let compact = CompactTransaction::from(&tx);
+        let full = Transaction::from(&compact);
+
+        assert_eq!(full, tx);
+        assert_eq!(table_assignments(&full), table_assignments(&tx));
+    }
+
+    #[test]
+    fn compact_typed_tx_rlp() {
+        let compact = CompactTransaction {
+            id: 1,
+            tx_type: TxType::Eip1559,
+            nonce: 3,
+            gas: 0x5208,
+            max_fee_per_gas: Word::from(0x3b9aca00),
+            max_priority_fee_per_gas: Word::from(0x3b9aca00),
+            callee_address: Some(MOCK_ACCOUNTS[1]),
+            value: Word::from(0x2a),
+            call_data: vec![0xde, 0xad, 0xbe, 0xef],
+            chain_id: 1,
+            v: 1,
+            r: Word::from(1),
+            s: Word::from(2),
+            access_list: Some(Default::default()),
+            ..Default::default()
+        };
+        let full = Transaction::from(&compact);
+
+        let eth_tx = EthTransaction::decode(&Rlp::new(&full.rlp_signed))
+            .expect("decode tx's rlp bytes shall not fail");
+        assert_eq!(eth_tx.hash, keccak256(&full.rlp_signed).into());
+        assert_eq!(eth_tx.nonce, compact.nonce.into());
+        assert_eq!(eth_tx.to, compact.callee_address);
+        assert_eq!(eth_tx.input.to_vec(), compact.call_data);
+        assert_eq!(full.rlp_unsigned[0], 0x02);
+        assert_eq!(CompactTransaction::from(&full), compact);
+    }
+}
@@ -353,6 +353,8 @@ impl<F: Field> RwRow<F> {
             self.aux2,
         ]
     }
+    /// RLC of the row, as the evm circuit rw lookups compress the rw table columns
+    /// (with a leading 1 for `q_enable`) under the lookup input challenge
     pub(crate) fn rlc(&self, randomness: F) -> F {
         let values = self.values();
         std::iter::once(&F::one())
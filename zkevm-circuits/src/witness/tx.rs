@@ -1,6 +1,7 @@
 use crate::{
     evm_circuit::{step::ExecutionState, util::rlc},
     table::TxContextFieldTag,
+    tx_circuit::TxHasher,
     util::{rlc_be_bytes, Challenges},
     witness::{
         rlp_fsm::{RlpStackOp, SmState},
@@ -27,16 +28,23 @@ use eth_types::{
 };
 use ethers_core::{
     types::TransactionRequest,
-    utils::{keccak256, rlp::Encodable},
+    utils::{
+        keccak256,
+        rlp::{self, Encodable},
+    },
 };
 use halo2_proofs::{
     circuit::Value,
-    halo2curves::{group::ff::PrimeField, secp256k1},
+    halo2curves::{group::ff::PrimeField, secp256k1, secp256k1::Secp256k1Affine},
 };
 use mock::MockTransaction;
 use num::Integer;
 use num_bigint::BigUint;
-use std::{cmp::Ordering, collections::BTreeMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
 
 use super::{step::step_convert, Call, ExecStep};
 
@@ -103,7 +111,143 @@ pub struct Transaction {
     pub steps: Vec<ExecStep>,
 }
 
+/// The inputs of the public key recovery of a signature: the signed message hash and
+/// the signature. A recovered public key is only reused for exactly the same inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecoveryKey {
+    /// Hash of the signed message
+    pub msg_hash: [u8; 32],
+    /// Recovery id
+    pub v: u8,
+    /// r of the signature
+    pub r: Word,
+    /// s of the signature
+    pub s: Word,
+}
+
+/// Public keys recovered from tx signatures, which can be shared across the witness
+/// generation of several circuits proving overlapping txs. A wrong entry can't make an
+/// invalid signature pass: the recovered public key is verified by the sig circuit.
+pub type RecoveryCache = Arc<RwLock<HashMap<RecoveryKey, Secp256k1Affine>>>;
+
+fn sign_data_with_msg_hash(
+    msg: &[u8],
+    msg_hash: [u8; 32],
+    v: u8,
+    r: &Word,
+    s: &Word,
+    recovery_cache: Option<&RecoveryCache>,
+) -> Result<SignData, Error> {
+    // r and s must be in [1, n - 1], `from_repr` only rejects the values >= n
+    if r.is_zero() || s.is_zero() {
+        return Err(Error::Signature);
+    }
+    let sig_r = ct_option_ok_or(secp256k1::Fq::from_repr(r.to_le_bytes()), Error::Signature)?;
+    let sig_s = ct_option_ok_or(secp256k1::Fq::from_repr(s.to_le_bytes()), Error::Signature)?;
+    let pk = match recovery_cache {
+        Some(cache) => {
+            let key = RecoveryKey {
+                msg_hash,
+                v,
+                r: *r,
+                s: *s,
+            };
+            let cached_pk = cache
+                .read()
+                .expect("recovery cache poisoned")
+                .get(&key)
+                .copied();
+            match cached_pk {
+                Some(pk) => pk,
+                None => {
+                    let pk = recover_pk2(v, r, s, &msg_hash)?;
+                    cache
+                        .write()
+                        .expect("recovery cache poisoned")
+                        .insert(key, pk);
+                    pk
+                }
+            }
+        }
+        None => recover_pk2(v, r, s, &msg_hash)?,
+    };
+    // msg_hash = msg_hash % q
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
+    let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
+    let msg_hash_le = biguint_to_32bytes_le(msg_hash);
+    let msg_hash = ct_option_ok_or(secp256k1::Fq::from_repr(msg_hash_le), Error::Signature)?;
+    Ok(SignData {
+        signature: (sig_r, sig_s, v),
+        pk,
+        msg: msg.to_vec().into(),
+        msg_hash,
+    })
+}
+
+/// An RLP encoding of a tx in one of its formats, i.e. the byte stream decoded by the RLP
+/// circuit and hashed by the tx circuit. The bytes are borrowed from the tx, so both
+/// circuits read the same stream without copying it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RlpStream<'a> {
+    /// Format of the encoding
+    pub format: Format,
+    bytes: &'a [u8],
+}
+
+impl<'a> RlpStream<'a> {
+    /// Returns the bytes of the encoding one by one
+    pub fn bytes(&self) -> impl ExactSizeIterator<Item = u8> + 'a {
+        self.bytes.iter().copied()
+    }
+
+    /// Returns the bytes of the encoding
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the number of bytes of the encoding
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns true if the encoding has no bytes
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
 impl Transaction {
+    /// Returns the signed RLP encoding of the tx, i.e. the preimage of its tx hash
+    pub fn rlp_hash_stream(&self) -> RlpStream<'_> {
+        let format = match self.tx_type {
+            TxType::Eip155 => TxHashEip155,
+            TxType::PreEip155 => TxHashPreEip155,
+            TxType::Eip1559 => TxHashEip1559,
+            TxType::L1Msg => L1MsgHash,
+            TxType::Eip2930 => TxHashEip2930,
+        };
+        RlpStream {
+            format,
+            bytes: &self.rlp_signed,
+        }
+    }
+
+    /// Returns the unsigned RLP encoding of the tx, i.e. the message signed by its sender,
+    /// or `None` for L1 msgs which are not signed
+    pub fn rlp_sign_stream(&self) -> Option<RlpStream<'_>> {
+        let format = match self.tx_type {
+            TxType::Eip155 => TxSignEip155,
+            TxType::PreEip155 => TxSignPreEip155,
+            TxType::Eip1559 => TxSignEip1559,
+            TxType::Eip2930 => TxSignEip2930,
+            TxType::L1Msg => return None,
+        };
+        Some(RlpStream {
+            format,
+            bytes: &self.rlp_unsigned,
+        })
+    }
+
     /// Return a fixed dummy pre-eip155 tx
     pub fn dummy(chain_id: u64) -> Self {
         let (dummy_tx, dummy_sig) = get_dummy_tx();
@@ -131,42 +275,116 @@ impl Transaction {
         }
     }
 
+    /// Expand an EIP-2098 compact signature (`r || yParity << 255 | s`) into the
+    /// `v`, `r` and `s` of a tx of type `tx_type` on `chain_id`
+    pub fn from_compact_sig(
+        tx_type: TxType,
+        chain_id: u64,
+        compact_sig: &[u8; 64],
+    ) -> Result<Signature, Error> {
+        let r = Word::from_big_endian(&compact_sig[..32]);
+        let y_parity_and_s = Word::from_big_endian(&compact_sig[32..]);
+        let y_parity = y_parity_and_s.bit(255) as u64;
+        let s = y_parity_and_s & ((Word::one() << 255) - 1);
+        let v = match tx_type {
+            TxType::PreEip155 => 27 + y_parity,
+            TxType::Eip155 => 2 * chain_id + 35 + y_parity,
+            TxType::Eip1559 | TxType::Eip2930 => y_parity,
+            TxType::L1Msg => return Err(Error::Signature),
+        };
+        Ok(Signature { r, s, v })
+    }
+
+    /// Set the signature of the tx from an EIP-2098 compact signature. The signed RLP
+    /// is re-encoded with the expanded signature, as it's the one the tx hash is
+    /// computed over.
+    pub fn with_compact_sig(mut self, compact_sig: &[u8; 64]) -> Result<Self, Error> {
+        let sig = Self::from_compact_sig(self.tx_type, self.chain_id, compact_sig)?;
+        self.rlp_signed = self.rlp_signed_with(&sig)?;
+        self.hash = H256(keccak256(&self.rlp_signed));
+        self.tx_data_gas_cost = tx_data_gas_cost(&self.rlp_signed);
+        self.v = sig.v;
+        self.r = sig.r;
+        self.s = sig.s;
+        Ok(self)
+    }
+
+    /// Signed RLP encoding of the tx with the signature `sig`, i.e. the fields of the
+    /// unsigned RLP encoding (without the EIP-155 chain id fields) followed by `sig`.
+    fn rlp_signed_with(&self, sig: &Signature) -> Result<Vec<u8>, Error> {
+        let (tx_type_prefix, rlp_unsigned) = match self.tx_type {
+            TxType::PreEip155 | TxType::Eip155 => (None, self.rlp_unsigned.as_slice()),
+            TxType::Eip1559 | TxType::Eip2930 => {
+                let (prefix, rlp_unsigned) =
+                    self.rlp_unsigned.split_first().ok_or(Error::Signature)?;
+                (Some(*prefix), rlp_unsigned)
+            }
+            TxType::L1Msg => return Err(Error::Signature),
+        };
+        // a malformed unsigned RLP encoding can't carry a signature
+        let fields = rlp::Rlp::new(rlp_unsigned);
+        let num_fields = match self.tx_type {
+            // nonce, gas price, gas, to, value and data
+            TxType::PreEip155 | TxType::Eip155 => 6,
+            _ => fields.item_count().map_err(|_| Error::Signature)?,
+        };
+        let mut stream = rlp::RlpStream::new_list(num_fields + 3);
+        for i in 0..num_fields {
+            let field = fields.at(i).map_err(|_| Error::Signature)?;
+            stream.append_raw(field.as_raw(), 1);
+        }
+        stream.append(&sig.v).append(&sig.r).append(&sig.s);
+
+        Ok(tx_type_prefix.into_iter().chain(stream.out()).collect())
+    }
+
     /// Sign data
     pub fn sign_data(&self) -> Result<SignData, Error> {
+        self.sign_data_with_hasher(|msg| keccak256(msg))
+    }
+
+    /// Sign data of a tx whose sighash is computed by `hasher` instead of keccak256
+    pub fn sign_data_with_hasher(
+        &self,
+        hasher: impl Fn(&[u8]) -> [u8; 32],
+    ) -> Result<SignData, Error> {
+        self.sign_data_with_cache(hasher, None)
+    }
+
+    /// Sign data of a tx whose sighash is computed by `hasher`, taking the public key
+    /// from `recovery_cache` if it has already been recovered from the same signature
+    /// of the same sighash
+    pub fn sign_data_with_cache(
+        &self,
+        hasher: impl Fn(&[u8]) -> [u8; 32],
+        recovery_cache: Option<&RecoveryCache>,
+    ) -> Result<SignData, Error> {
         if self.r.is_zero() && self.s.is_zero() && self.v == 0 {
             return Ok(SignData::default());
         }
-        let sig_r_le = self.r.to_le_bytes();
-        let sig_s_le = self.s.to_le_bytes();
-        let sig_r = ct_option_ok_or(secp256k1::Fq::from_repr(sig_r_le), Error::Signature)?;
-        let sig_s = ct_option_ok_or(secp256k1::Fq::from_repr(sig_s_le), Error::Signature)?;
-        let msg = self.rlp_unsigned.clone().into();
-        let msg_hash = keccak256(&self.rlp_unsigned);
         let v = self.tx_type.get_recovery_id(self.v);
-        let pk = recover_pk2(v, &self.r, &self.s, &msg_hash)?;
-        // msg_hash = msg_hash % q
-        let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
-        let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
-        let msg_hash_le = biguint_to_32bytes_le(msg_hash);
-        let msg_hash = ct_option_ok_or(secp256k1::Fq::from_repr(msg_hash_le), Error::Signature)?;
-        Ok(SignData {
-            signature: (sig_r, sig_s, v),
-            pk,
-            msg,
+        let msg_hash = hasher(&self.rlp_unsigned);
+        sign_data_with_msg_hash(
+            &self.rlp_unsigned,
             msg_hash,
-        })
+            v,
+            &self.r,
+            &self.s,
+            recovery_cache,
+        )
     }
 
     /// Assignments for tx table, split into tx_data (all fields except
     /// calldata) and tx_calldata
 
-    /// Assignments for tx table
+    /// Assignments for tx table, with `TxSignHash` and `TxHash` computed by `hasher`
     pub fn table_assignments_fixed<F: Field>(
         &self,
+        hasher: &TxHasher,
         challenges: Challenges<Value<F>>,
     ) -> Vec<[Value<F>; 5]> {
-        let tx_hash_be_bytes = keccak256(&self.rlp_signed);
-        let tx_sign_hash_be_bytes = keccak256(&self.rlp_unsigned);
+        let tx_hash_be_bytes = hasher.hash(&self.rlp_signed);
+        let tx_sign_hash_be_bytes = hasher.hash(&self.rlp_unsigned);
         let (access_list_address_size, access_list_storage_key_size) =
             access_list_size(&self.access_list);
 
@@ -261,7 +479,12 @@ impl Transaction {
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::ChainID as u64)),
                 Value::known(F::zero()),
-                Value::known(F::from(self.chain_id)),
+                // l1 msg has no chain id in its hash
+                Value::known(F::from(if self.tx_type.is_l1_msg() {
+                    0
+                } else {
+                    self.chain_id
+                })),
                 Value::known(F::zero()),
             ],
             [
@@ -449,29 +672,13 @@ impl Transaction {
         is_hash: bool,
         challenges: &Challenges<Value<F>>,
     ) -> Vec<RlpFsmWitnessRow<F>> {
-        let (rlp_bytes, format) = if is_hash {
-            (
-                self.rlp_signed.clone(),
-                match self.tx_type {
-                    TxType::Eip155 => TxHashEip155,
-                    TxType::PreEip155 => TxHashPreEip155,
-                    TxType::Eip1559 => TxHashEip1559,
-                    TxType::L1Msg => L1MsgHash,
-                    TxType::Eip2930 => TxHashEip2930,
-                },
-            )
+        let stream = if is_hash {
+            self.rlp_hash_stream()
         } else {
-            (
-                self.rlp_unsigned.clone(),
-                match self.tx_type {
-                    TxType::Eip155 => TxSignEip155,
-                    TxType::PreEip155 => TxSignPreEip155,
-                    TxType::Eip1559 => TxSignEip1559,
-                    TxType::Eip2930 => TxSignEip2930,
-                    _ => unreachable!("tx type {:?} not supported", self.tx_type),
-                },
-            )
+            self.rlp_sign_stream()
+                .unwrap_or_else(|| unreachable!("tx type {:?} not supported", self.tx_type))
         };
+        let (rlp_bytes, format) = (stream.as_slice(), stream.format);
 
         let tx_id = self.id as u64;
         let mut witness = vec![];
@@ -1093,22 +1300,14 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
         let tx_id = self.id as u64;
         let r = challenges.keccak_input();
 
-        let (hash_format, sign_format) = match self.tx_type {
-            TxType::Eip155 => (TxHashEip155, Some(TxSignEip155)),
-            TxType::PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
-            TxType::Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
-            TxType::Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
-            TxType::L1Msg => (L1MsgHash, None),
-        };
-
-        let get_table = |rlp_bytes: &Vec<u8>, format: Format| {
-            let n = rlp_bytes.len();
-            rlp_bytes
-                .iter()
+        let get_table = |stream: RlpStream<'_>| {
+            let (n, format) = (stream.len(), stream.format);
+            stream
+                .bytes()
                 .enumerate()
                 .scan(
                     (Value::known(F::zero()), Value::known(F::zero())),
-                    |(rlc, gas_cost_acc), (i, &byte_value)| {
+                    |(rlc, gas_cost_acc), (i, byte_value)| {
                         let byte_cost = if byte_value == 0 { 4 } else { 16 };
                         *rlc = *rlc * r + Value::known(F::from(byte_value as u64));
                         *gas_cost_acc = *gas_cost_acc + Value::known(F::from(byte_cost));
@@ -1126,9 +1325,9 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
                 .collect::<Vec<_>>()
         };
 
-        let hash_table = get_table(&self.rlp_signed, hash_format);
-        if let Some(sign_format) = sign_format {
-            let sign_table = get_table(&self.rlp_unsigned, sign_format);
+        let hash_table = get_table(self.rlp_hash_stream());
+        if let Some(sign_stream) = self.rlp_sign_stream() {
+            let sign_table = get_table(sign_stream);
             [sign_table, hash_table].concat()
         } else {
             hash_table
@@ -1310,13 +1509,24 @@ pub(super) fn tx_convert(
 
 #[cfg(test)]
 mod tests {
-    use crate::witness::{tx::Challenges, RlpTag, Tag, Transaction};
+    use crate::witness::{
+        tx::Challenges,
+        Format::{L1MsgHash, TxHashEip2930, TxSignEip2930},
+        RlpFsmWitnessGen, RlpTag, Tag, Transaction,
+    };
     use eth_types::{
         evm_types::gas_utils::tx_data_gas_cost, geth_types::TxType, Address, ToBigEndian, ToScalar,
+        Word, H256,
     };
     use ethers_core::{
-        types::{Transaction as EthTransaction, TransactionRequest},
-        utils::rlp::{Decodable, Rlp},
+        types::{
+            transaction::{eip2718::TypedTransaction, eip2930::Eip2930TransactionRequest},
+            Signature, Transaction as EthTransaction, TransactionRequest,
+        },
+        utils::{
+            keccak256,
+            rlp::{Decodable, Rlp},
+        },
     };
     use halo2_proofs::{circuit::Value, dev::unwrap_value, halo2curves::bn256::Fr};
 
@@ -1551,4 +1761,173 @@ mod tests {
             Fr::from(tx_data_gas_cost(&tx.rlp_signed)),
         );
     }
+
+    #[test]
+    fn test_rlp_eip2930_type_prefix() {
+        let typed_tx: TypedTransaction = Eip2930TransactionRequest::new(
+            TransactionRequest::new()
+                .chain_id(1)
+                .nonce(3)
+                .gas_price(0x3b9aca00)
+                .gas(0x5208)
+                .to(Address::repeat_byte(0x11))
+                .value(0x2a)
+                .data(vec![0xde, 0xad, 0xbe, 0xef]),
+            Default::default(),
+        )
+        .into();
+        let sig = Signature {
+            r: 1.into(),
+            s: 2.into(),
+            v: 1,
+        };
+        let tx = Transaction::new_from_rlp_bytes(
+            1,
+            TxType::Eip2930,
+            typed_tx.rlp_signed(&sig).to_vec(),
+            typed_tx.rlp().to_vec(),
+        );
+        let mock_challenges = Challenges::mock(
+            Value::known(Fr::from(0x1ab)),
+            Value::known(Fr::from(0x10000)),
+            Value::known(Fr::from(0x100)),
+        );
+
+        for (is_hash, format) in [(false, TxSignEip2930), (true, TxHashEip2930)] {
+            let witness_table = tx.gen_rlp_witness(is_hash, &mock_challenges);
+            let rlp_table = witness_table
+                .iter()
+                .filter(|row| row.rlp_table.is_output)
+                .map(|row| row.rlp_table)
+                .collect::<Vec<_>>();
+
+            // the first output row is the EIP-2718 type prefix byte
+            assert_eq!(rlp_table[0].format, format);
+            assert_eq!(rlp_table[0].rlp_tag, Tag::TxType.into());
+            assert_eq!(rlp_table[0].tag_length, 1);
+            assert_eq!(unwrap_value(rlp_table[0].tag_value), Fr::from(0x01));
+            assert_eq!(unwrap_value(rlp_table[0].tag_bytes_rlc), Fr::from(0x01));
+        }
+    }
+
+    #[test]
+    fn test_rlp_streams_match_owned_bytes() {
+        let typed_tx: TypedTransaction = Eip2930TransactionRequest::new(
+            TransactionRequest::new()
+                .chain_id(1)
+                .nonce(3)
+                .gas_price(0x3b9aca00)
+                .gas(0x5208)
+                .to(Address::repeat_byte(0x11))
+                .value(0x2a)
+                .data(vec![0xde, 0xad, 0xbe, 0xef]),
+            Default::default(),
+        )
+        .into();
+        let sig = Signature {
+            r: 1.into(),
+            s: 2.into(),
+            v: 1,
+        };
+        let tx = Transaction::new_from_rlp_bytes(
+            1,
+            TxType::Eip2930,
+            typed_tx.rlp_signed(&sig).to_vec(),
+            typed_tx.rlp().to_vec(),
+        );
+
+        let hash_stream = tx.rlp_hash_stream();
+        assert_eq!(hash_stream.format, TxHashEip2930);
+        assert_eq!(hash_stream.len(), tx.rlp_signed.len());
+        assert_eq!(hash_stream.bytes().collect::<Vec<_>>(), tx.rlp_signed);
+
+        let sign_stream = tx.rlp_sign_stream().expect("eip2930 txs are signed");
+        assert_eq!(sign_stream.format, TxSignEip2930);
+        assert_eq!(sign_stream.len(), tx.rlp_unsigned.len());
+        assert_eq!(sign_stream.bytes().collect::<Vec<_>>(), tx.rlp_unsigned);
+
+        // the data table the RLP circuit looks up into is built from the same streams
+        let mock_challenges = Challenges::mock(
+            Value::known(Fr::from(0x1ab)),
+            Value::known(Fr::from(0x10000)),
+            Value::known(Fr::from(0x100)),
+        );
+        let data_table = RlpFsmWitnessGen::<Fr>::gen_data_table(&tx, &mock_challenges);
+        for stream in [sign_stream, hash_stream] {
+            let table_bytes = data_table
+                .iter()
+                .filter(|row| row.format == stream.format)
+                .map(|row| row.byte_value)
+                .collect::<Vec<_>>();
+            assert_eq!(table_bytes, stream.as_slice());
+        }
+
+        let l1_msg = Transaction {
+            tx_type: TxType::L1Msg,
+            ..tx
+        };
+        assert!(l1_msg.rlp_sign_stream().is_none());
+        assert_eq!(l1_msg.rlp_hash_stream().format, L1MsgHash);
+    }
+
+    #[test]
+    fn test_compact_sig() {
+        let tx = Transaction::from(mock::CORRECT_MOCK_TXS[0].clone());
+        let y_parity = tx.tx_type.get_recovery_id(tx.v);
+        let mut compact_sig = [0u8; 64];
+        tx.r.to_big_endian(&mut compact_sig[..32]);
+        (tx.s | (Word::from(y_parity) << 255)).to_big_endian(&mut compact_sig[32..]);
+
+        // neither the signed RLP nor the hash of the unsigned tx carry the signature
+        let unsigned = Transaction {
+            v: 0,
+            r: Word::zero(),
+            s: Word::zero(),
+            rlp_signed: vec![],
+            hash: H256::zero(),
+            tx_data_gas_cost: 0,
+            ..tx.clone()
+        };
+        let expanded = unsigned
+            .with_compact_sig(&compact_sig)
+            .expect("an eip155 tx accepts a compact signature");
+        assert_eq!((expanded.v, expanded.r, expanded.s), (tx.v, tx.r, tx.s));
+        assert_eq!(expanded.rlp_signed, tx.rlp_signed);
+        assert_eq!(expanded.hash, H256(keccak256(&tx.rlp_signed)));
+        assert_eq!(expanded.tx_data_gas_cost, tx.tx_data_gas_cost);
+
+        // the sender is recovered from the expanded signature
+        let sign_data = expanded.sign_data().expect("the signature is valid");
+        assert_eq!(sign_data.get_addr(), tx.caller_address);
+
+        assert!(Transaction::from_compact_sig(TxType::L1Msg, tx.chain_id, &compact_sig).is_err());
+
+        // the signature of a typed tx follows all the fields of its unsigned RLP
+        let typed_tx: TypedTransaction = Eip2930TransactionRequest::new(
+            TransactionRequest::new()
+                .chain_id(1)
+                .nonce(3)
+                .gas_price(0x3b9aca00)
+                .gas(0x5208)
+                .to(Address::repeat_byte(0x11))
+                .value(0x2a)
+                .data(vec![0xde, 0xad, 0xbe, 0xef]),
+            Default::default(),
+        )
+        .into();
+        let sig = Signature {
+            r: 1.into(),
+            s: 2.into(),
+            v: 1,
+        };
+        let mut compact_sig = [0u8; 64];
+        sig.r.to_big_endian(&mut compact_sig[..32]);
+        (sig.s | (Word::one() << 255)).to_big_endian(&mut compact_sig[32..]);
+        let expanded =
+            Transaction::new_from_rlp_bytes(1, TxType::Eip2930, vec![], typed_tx.rlp().to_vec())
+                .with_compact_sig(&compact_sig)
+                .expect("an eip2930 tx accepts a compact signature");
+        assert_eq!(expanded.rlp_signed, typed_tx.rlp_signed(&sig).to_vec());
+        assert_eq!(expanded.hash, typed_tx.hash(&sig));
+    }
 }